@@ -1,55 +1,165 @@
-use regex::Regex;
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::io::{self, BufRead, Read};
 
 pub fn day1_part1(inp: &str) -> u64 {
-    let parsed_input = inp
-        .lines()
-        .map(|line| {
-            let (first_num, second_num) = line.split_once("   ").unwrap();
-            (
-                first_num.parse::<u64>().unwrap(),
-                second_num.parse::<u64>().unwrap(),
-            )
-        })
-        .collect::<Vec<_>>();
-    let mut first_nums = parsed_input.iter().map(|x| x.0).collect::<Vec<_>>();
-    first_nums.sort();
-    let mut second_nums = parsed_input.iter().map(|x| x.1).collect::<Vec<_>>();
-    second_nums.sort();
-    first_nums
-        .into_iter()
-        .zip(second_nums)
-        .map(|(first_num, second_num)| first_num.abs_diff(second_num))
-        .sum()
+    LocationLists::new(inp).total_distance()
 }
 
 pub fn day1_part2(inp: &str) -> u64 {
-    let parsed_input = inp
-        .lines()
-        .map(|line| {
-            let (first_num, second_num) = line.split_once("   ").unwrap();
-            (
-                first_num.parse::<u64>().unwrap(),
-                second_num.parse::<u64>().unwrap(),
-            )
-        })
-        .collect::<Vec<_>>();
-    let first_nums = parsed_input.iter().map(|x| x.0).collect::<Vec<_>>();
-    let second_nums = parsed_input.iter().map(|x| x.1).collect::<Vec<_>>();
-    let mut second_nums_map = HashMap::new();
-    for num in second_nums {
-        second_nums_map
-            .entry(num)
-            .and_modify(|n| *n += 1)
-            .or_insert(1_u64);
-    }
-    first_nums
-        .into_iter()
-        .map(|num| {
-            let &occurrences = second_nums_map.get(&num).unwrap_or(&0);
-            num * occurrences
-        })
-        .sum()
+    LocationLists::new(inp).similarity_score()
+}
+
+// The two columns of location IDs, parsed once. Both lists are kept sorted.
+#[derive(Debug, Clone)]
+pub struct LocationLists {
+    left: Vec<u64>,
+    right: Vec<u64>,
+}
+
+impl LocationLists {
+    pub fn new(inp: &str) -> Self {
+        Self::parse(inp).unwrap()
+    }
+
+    // Columns can be separated by any whitespace, and blank lines are skipped. Errors say which
+    // line (counting from 1) was wrong.
+    pub fn parse(inp: &str) -> Result<Self, String> {
+        Self::from_reader(inp.as_bytes())
+    }
+
+    // Reads the lists a line at a time, so only the lists themselves are held in memory, never
+    // the whole input.
+    pub fn from_reader(mut reader: impl BufRead) -> Result<Self, String> {
+        let mut left = Vec::new();
+        let mut right = Vec::new();
+        let mut line = String::new();
+        for line_num in 1.. {
+            line.clear();
+            let bytes_read = reader
+                .read_line(&mut line)
+                .map_err(|e| format!("line {line_num}: {e}"))?;
+            if bytes_read == 0 {
+                break;
+            }
+            let columns = line.split_ascii_whitespace().collect::<Vec<_>>();
+            match columns[..] {
+                [] => continue,
+                [first_num, second_num] => {
+                    for (num, list) in [(first_num, &mut left), (second_num, &mut right)] {
+                        list.push(num.parse::<u64>().map_err(|e| {
+                            format!("line {line_num}: invalid location ID {num:?}: {e}")
+                        })?);
+                    }
+                }
+                _ => {
+                    return Err(format!(
+                        "line {line_num}: expected 2 columns, found {}",
+                        columns.len()
+                    ))
+                }
+            }
+        }
+        left.sort();
+        right.sort();
+        Ok(LocationLists { left, right })
+    }
+
+    pub fn left(&self) -> &[u64] {
+        &self.left
+    }
+
+    pub fn right(&self) -> &[u64] {
+        &self.right
+    }
+
+    // Smallest with smallest, second smallest with second smallest, and so on.
+    pub fn matched_pairs(&self) -> Vec<(u64, u64)> {
+        self.left
+            .iter()
+            .copied()
+            .zip(self.right.iter().copied())
+            .collect()
+    }
+
+    pub fn total_distance(&self) -> u64 {
+        self.matched_pairs()
+            .into_iter()
+            .map(|(first_num, second_num)| first_num.abs_diff(second_num))
+            .sum()
+    }
+
+    pub fn similarity_score(&self) -> u64 {
+        // Both lists are sorted, so walk them together rather than building a map of counts.
+        let mut score = 0;
+        let mut right_pos = 0;
+        let mut occurrences = 0;
+        for (n, &num) in self.left.iter().enumerate() {
+            // equal numbers are next to each other, so only count each one in the right list once
+            if n == 0 || self.left[n - 1] != num {
+                while right_pos < self.right.len() && self.right[right_pos] < num {
+                    right_pos += 1;
+                }
+                occurrences = 0;
+                while right_pos < self.right.len() && self.right[right_pos] == num {
+                    right_pos += 1;
+                    occurrences += 1;
+                }
+            }
+            score += num * occurrences;
+        }
+        score
+    }
+
+    // (left median, right median), or None if the lists are empty.
+    pub fn medians(&self) -> Option<(f64, f64)> {
+        Some((day1_median(&self.left)?, day1_median(&self.right)?))
+    }
+
+    // IDs that appear in the left list but never in the right one.
+    pub fn only_in_left(&self) -> BTreeSet<u64> {
+        let right = self.right.iter().collect::<HashSet<_>>();
+        self.left
+            .iter()
+            .filter(|num| !right.contains(num))
+            .copied()
+            .collect()
+    }
+
+    // IDs that appear in the right list but never in the left one.
+    pub fn only_in_right(&self) -> BTreeSet<u64> {
+        let left = self.left.iter().collect::<HashSet<_>>();
+        self.right
+            .iter()
+            .filter(|num| !left.contains(num))
+            .copied()
+            .collect()
+    }
+
+    // For every ID, how many times it appears in (left list, right list).
+    pub fn counts(&self) -> BTreeMap<u64, (u64, u64)> {
+        let mut counts = BTreeMap::new();
+        for &num in &self.left {
+            counts.entry(num).or_insert((0, 0)).0 += 1;
+        }
+        for &num in &self.right {
+            counts.entry(num).or_insert((0, 0)).1 += 1;
+        }
+        counts
+    }
+}
+
+fn day1_median(sorted_nums: &[u64]) -> Option<f64> {
+    if sorted_nums.is_empty() {
+        return None;
+    }
+    let middle = sorted_nums.len() / 2;
+    if sorted_nums.len() % 2 == 1 {
+        Some(sorted_nums[middle] as f64)
+    } else {
+        Some((sorted_nums[middle - 1] as f64 + sorted_nums[middle] as f64) / 2.0)
+    }
 }
 
 pub fn day2_part1(inp: &str) -> u64 {
@@ -103,265 +213,842 @@ fn day2_report_is_safe(report: &[u64]) -> bool {
     all_increasing || all_decreasing
 }
 
+// The puzzle says operands are 1-3 digits, so longer ones aren't valid muls.
+const DAY3_PUZZLE_MAX_OPERAND_DIGITS: u32 = 3;
+
 pub fn day3_part1(inp: &str) -> u64 {
-    let re = Regex::new("mul\\(([0-9]+),([0-9]+)\\)").unwrap();
-    re.captures_iter(inp)
-        .map(|captures| {
-            let first_num = &captures[1].parse::<u64>().unwrap();
-            let second_num = &captures[2].parse::<u64>().unwrap();
-            first_num * second_num
-        })
-        .sum()
+    day3_scan(inp.as_bytes(), Some(DAY3_PUZZLE_MAX_OPERAND_DIGITS))
+        .all_muls
+        .unwrap()
 }
 
 pub fn day3_part2(inp: &str) -> u64 {
-    let re = Regex::new("do\\(\\)|don't\\(\\)|mul\\(([0-9]+),([0-9]+)\\)").unwrap();
-    let mut enabled = true;
-    let mut sum = 0;
-    for captures in re.captures_iter(inp) {
-        if &captures[0] == "do()" {
-            enabled = true;
-            continue;
-        } else if captures[0].starts_with("don't") {
-            enabled = false;
-            continue;
-        }
-        if !enabled {
-            continue;
-        }
-        let first_num = &captures[1].parse::<u64>().unwrap();
-        let second_num = &captures[2].parse::<u64>().unwrap();
-        sum += first_num * second_num;
+    day3_scan(inp.as_bytes(), Some(DAY3_PUZZLE_MAX_OPERAND_DIGITS))
+        .enabled_muls
+        .unwrap()
+}
+
+// If `max_operand_digits` is None, operands can be any length that fits in a u64.
+pub fn day3_scan(memory: &[u8], max_operand_digits: Option<u32>) -> Day3Totals {
+    let mut scanner = Day3Scanner::new().with_max_operand_digits(max_operand_digits);
+    scanner.feed(memory);
+    scanner.totals()
+}
+
+// Scans the memory a buffer at a time, so it never needs to be held in memory all at once.
+pub fn day3_scan_reader(
+    mut reader: impl Read,
+    max_operand_digits: Option<u32>,
+) -> io::Result<Day3Totals> {
+    let mut scanner = Day3Scanner::new().with_max_operand_digits(max_operand_digits);
+    let mut buffer = [0; 8192];
+    loop {
+        let bytes_read = match reader.read(&mut buffer) {
+            Ok(0) => return Ok(scanner.totals()),
+            Ok(bytes_read) => bytes_read,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        scanner.feed(&buffer[..bytes_read]);
     }
-    sum
 }
 
-pub fn day4_part1(inp: &str) -> u64 {
-    let grid: Vec<Vec<_>> = inp.lines().map(|line| line.chars().collect()).collect();
-    // find the Xs in the grid
-    let mut x_locs = Vec::new();
-    for (y, line) in grid.iter().enumerate() {
-        for (x, &chr) in line.iter().enumerate() {
-            if chr == 'X' {
-                x_locs.push((x, y));
-            }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Day3Instruction {
+    Mul(u64, u64),
+    Do,
+    Dont,
+}
+
+// Each sum is None once it has overflowed a u64.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day3Totals {
+    // Sum of every mul, as in part 1.
+    pub all_muls: Option<u64>,
+    // Sum of the muls that weren't disabled by a don't(), as in part 2.
+    pub enabled_muls: Option<u64>,
+}
+
+impl Default for Day3Totals {
+    fn default() -> Self {
+        Day3Totals {
+            all_muls: Some(0),
+            enabled_muls: Some(0),
         }
     }
-    // for each X, count the number of words from it
-    x_locs
-        .into_iter()
-        .map(|point| day4_count_words_from_point(&grid, point))
-        .sum()
 }
 
-fn day4_count_words_from_point(grid: &[Vec<char>], (x, y): (usize, usize)) -> u64 {
-    let grid_width = grid[0].len();
-    let grid_height = grid.len();
-    let (x, y): (i64, i64) = (x.try_into().unwrap(), y.try_into().unwrap());
-    let mut word_coords = vec![
-        [(x, y), (x, y + 1), (x, y + 2), (x, y + 3)],
-        [(x, y), (x, y - 1), (x, y - 2), (x, y - 3)],
-        [(x, y), (x + 1, y), (x + 2, y), (x + 3, y)],
-        [(x, y), (x - 1, y), (x - 2, y), (x - 3, y)],
-        [(x, y), (x + 1, y + 1), (x + 2, y + 2), (x + 3, y + 3)],
-        [(x, y), (x + 1, y - 1), (x + 2, y - 2), (x + 3, y - 3)],
-        [(x, y), (x - 1, y + 1), (x - 2, y + 2), (x - 3, y + 3)],
-        [(x, y), (x - 1, y - 1), (x - 2, y - 2), (x - 3, y - 3)],
-    ];
-    word_coords.retain(|coords| {
-        !coords.iter().any(|&(x1, y1)| {
-            x1 < 0
-                || y1 < 0
-                || x1 >= grid_width.try_into().unwrap()
-                || y1 >= grid_height.try_into().unwrap()
-        })
-    });
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Day3RejectReason {
+    // An operand had more digits than the scanner allows.
+    TooManyDigits { max_digits: u32 },
+    // An operand or the product didn't fit in a u64.
+    Overflow,
+}
 
-    let mut count = 0;
-    for coords in word_coords {
-        let word = coords
-            .iter()
-            .map(|&(x1, y1)| grid[y1 as usize][x1 as usize])
-            .collect::<String>();
-        if word == "XMAS" {
-            count += 1;
-        }
+// A well formed mul instruction that was ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Day3Rejection {
+    // Byte offset of the 'm' in the memory.
+    pub offset: u64,
+    pub reason: Day3RejectReason,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Day3ScanState {
+    // Not part way through any instruction.
+    Idle,
+    // Matched the first `len` bytes of a literal: "mul(", "do()" or "don't()".
+    Literal(&'static [u8], usize),
+    // Operands are None once they've overflowed, but are still scanned so the rejection can be
+    // reported.
+    FirstNum(Option<u64>, u32),
+    SecondNum((Option<u64>, u32), Option<u64>, u32),
+}
+
+// Finds mul, do and don't instructions in corrupted memory, one byte at a time. Memory can be
+// fed in chunks of any size, and instructions split across chunks are still found.
+pub struct Day3Scanner {
+    state: Day3ScanState,
+    // If set, muls with longer operands are rejected. The puzzle allows up to 3 digits.
+    max_operand_digits: Option<u32>,
+    enabled: bool,
+    totals: Day3Totals,
+    // Both only kept if asked for, so scanning a large input doesn't use memory proportional to it.
+    record_instructions: bool,
+    instructions: Vec<Day3Instruction>,
+    record_rejections: bool,
+    rejections: Vec<Day3Rejection>,
+    // Number of bytes fed so far, and the offset of the instruction being scanned.
+    offset: u64,
+    instruction_start: u64,
+}
+
+impl Default for Day3Scanner {
+    fn default() -> Self {
+        Self::new()
     }
-    count
 }
 
-pub fn day4_part2(inp: &str) -> usize {
-    let grid: Vec<Vec<_>> = inp.lines().map(|line| line.chars().collect()).collect();
-    // find the Ms in the grid
-    let mut m_locs = Vec::new();
-    for (y, line) in grid.iter().enumerate() {
-        for (x, &chr) in line.iter().enumerate() {
-            if chr == 'M' {
-                m_locs.push((x, y));
-            }
+impl Day3Scanner {
+    pub fn new() -> Self {
+        Day3Scanner {
+            state: Day3ScanState::Idle,
+            max_operand_digits: None,
+            enabled: true,
+            totals: Day3Totals::default(),
+            record_instructions: false,
+            instructions: Vec::new(),
+            record_rejections: false,
+            rejections: Vec::new(),
+            offset: 0,
+            instruction_start: 0,
         }
     }
-    let word_centres = m_locs
-        .into_iter()
-        .flat_map(|point| day4_part_2_get_word_centres(&grid, point))
-        .collect::<Vec<_>>();
 
-    let mut freq_map = HashMap::new();
-    for word_centre in word_centres {
-        freq_map
-            .entry(word_centre)
-            .and_modify(|n| *n += 1)
-            .or_insert(1_u64);
-    }
-    freq_map.retain(|_, count| *count > 1);
-    freq_map.len()
-}
-
-fn day4_part_2_get_word_centres(grid: &[Vec<char>], (x, y): (usize, usize)) -> Vec<(i64, i64)> {
-    let grid_width = grid[0].len();
-    let grid_height = grid.len();
-    let (x, y): (i64, i64) = (x.try_into().unwrap(), y.try_into().unwrap());
-    let mut word_coords = vec![
-        [(x, y), (x + 1, y + 1), (x + 2, y + 2)],
-        [(x, y), (x + 1, y - 1), (x + 2, y - 2)],
-        [(x, y), (x - 1, y + 1), (x - 2, y + 2)],
-        [(x, y), (x - 1, y - 1), (x - 2, y - 2)],
-    ];
-    word_coords.retain(|coords| {
-        !coords.iter().any(|&(x1, y1)| {
-            x1 < 0
-                || y1 < 0
-                || x1 >= grid_width.try_into().unwrap()
-                || y1 >= grid_height.try_into().unwrap()
-        })
-    });
+    // None means no limit, which is the default.
+    pub fn with_max_operand_digits(mut self, max_digits: Option<u32>) -> Self {
+        self.max_operand_digits = max_digits;
+        self
+    }
 
-    let mut word_centres = Vec::new();
-    for coords in word_coords {
-        let word = coords
-            .iter()
-            .map(|&(x1, y1)| grid[y1 as usize][x1 as usize])
-            .collect::<String>();
-        if word == "MAS" {
-            word_centres.push(coords[1]);
+    // Keeps the instructions found, for `take_instructions`.
+    pub fn with_recorded_instructions(mut self) -> Self {
+        self.record_instructions = true;
+        self
+    }
+
+    // Keeps the muls that were ignored, for `rejections`.
+    pub fn with_recorded_rejections(mut self) -> Self {
+        self.record_rejections = true;
+        self
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            if let Some(instruction) = self.step(byte) {
+                self.apply(instruction);
+            }
+            self.offset += 1;
         }
     }
-    word_centres
-}
 
-pub fn day5_part1(inp: &str) -> u32 {
-    let (first_part, second_part) = inp.split_once("\n\n").unwrap();
-    let page_ordering_rules = first_part
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once('|').unwrap();
-            (a.parse().unwrap(), b.parse().unwrap())
-        })
-        .collect::<HashSet<(u32, u32)>>();
+    pub fn totals(&self) -> Day3Totals {
+        self.totals
+    }
 
-    second_part
-        .lines()
-        .map(|line| {
-            line.split(',')
-                .map(|string| string.parse().unwrap())
-                .collect::<Vec<u32>>()
-        })
-        .filter(|update| {
-            for (n, &a) in update.iter().enumerate() {
-                for &b in update.iter().skip(n + 1) {
-                    if !page_ordering_rules.contains(&(a, b)) {
-                        return false;
-                    }
+    // Returns the instructions found since this was last called. Always empty unless the scanner
+    // was made `with_recorded_instructions`.
+    pub fn take_instructions(&mut self) -> Vec<Day3Instruction> {
+        std::mem::take(&mut self.instructions)
+    }
+
+    // Always empty unless the scanner was made `with_recorded_rejections`.
+    pub fn rejections(&self) -> &[Day3Rejection] {
+        &self.rejections
+    }
+
+    fn apply(&mut self, instruction: Day3Instruction) {
+        match instruction {
+            Day3Instruction::Mul(first_num, second_num) => {
+                let Some(product) = first_num.checked_mul(second_num) else {
+                    self.reject(Day3RejectReason::Overflow);
+                    return;
+                };
+                let totals = &mut self.totals;
+                totals.all_muls = totals.all_muls.and_then(|sum| sum.checked_add(product));
+                if self.enabled {
+                    totals.enabled_muls =
+                        totals.enabled_muls.and_then(|sum| sum.checked_add(product));
                 }
             }
-            true
-        })
-        .map(|update| update[update.len() / 2])
-        .sum()
-}
+            Day3Instruction::Do => self.enabled = true,
+            Day3Instruction::Dont => self.enabled = false,
+        }
+        if self.record_instructions {
+            self.instructions.push(instruction);
+        }
+    }
 
-pub fn day5_part2(inp: &str) -> u32 {
-    let (first_part, second_part) = inp.split_once("\n\n").unwrap();
-    let page_ordering_rules = first_part
-        .lines()
-        .map(|line| {
-            let (a, b) = line.split_once('|').unwrap();
-            (a.parse().unwrap(), b.parse().unwrap())
-        })
-        .collect::<HashSet<(u32, u32)>>();
+    fn reject(&mut self, reason: Day3RejectReason) {
+        if self.record_rejections {
+            self.rejections.push(Day3Rejection {
+                offset: self.instruction_start,
+                reason,
+            });
+        }
+    }
 
-    second_part
-        .lines()
-        .map(|line| {
-            line.split(',')
-                .map(|string| string.parse().unwrap())
-                .collect::<Vec<u32>>()
-        })
-        .filter(|update| {
-            for (n, &a) in update.iter().enumerate() {
-                for &b in update.iter().skip(n + 1) {
-                    if !page_ordering_rules.contains(&(a, b)) {
-                        return true;
-                    }
-                }
+    // Checks the operands of a complete mul instruction.
+    fn check_mul(
+        &mut self,
+        first_num: Option<u64>,
+        first_digits: u32,
+        second_num: Option<u64>,
+        second_digits: u32,
+    ) -> Option<Day3Instruction> {
+        if let Some(max_digits) = self.max_operand_digits {
+            if first_digits > max_digits || second_digits > max_digits {
+                self.reject(Day3RejectReason::TooManyDigits { max_digits });
+                return None;
             }
-            false
-        })
-        .map(|update| {
-            let mut new_update = Vec::new();
-            for page_num in update.into_iter() {
-                let mut havent_inserted = true;
-                for (n, &page_num1) in new_update.iter().enumerate() {
-                    if page_ordering_rules.contains(&(page_num1, page_num)) {
-                        continue;
-                    } else {
-                        new_update.insert(n, page_num);
-                        havent_inserted = false;
-                        break;
-                    }
-                }
-                if havent_inserted {
-                    new_update.push(page_num);
+        }
+        match (first_num, second_num) {
+            (Some(first_num), Some(second_num)) => {
+                Some(Day3Instruction::Mul(first_num, second_num))
+            }
+            _ => {
+                self.reject(Day3RejectReason::Overflow);
+                None
+            }
+        }
+    }
+
+    fn step(&mut self, byte: u8) -> Option<Day3Instruction> {
+        let (next_state, instruction) = match (self.state, byte) {
+            (Day3ScanState::Literal(literal, len), _) if literal[len] == byte => {
+                if len + 1 < literal.len() {
+                    (Day3ScanState::Literal(literal, len + 1), None)
+                } else if literal == b"mul(" {
+                    (Day3ScanState::FirstNum(Some(0), 0), None)
+                } else if literal == b"do()" {
+                    (Day3ScanState::Idle, Some(Day3Instruction::Do))
+                } else {
+                    (Day3ScanState::Idle, Some(Day3Instruction::Dont))
                 }
             }
-            new_update[new_update.len() / 2]
-        })
-        .sum()
+            // "do" could still turn out to be "don't()"
+            (Day3ScanState::Literal(b"do()", 2), b'n') => {
+                (Day3ScanState::Literal(b"don't()", 3), None)
+            }
+            (Day3ScanState::FirstNum(num, digits), b'0'..=b'9') => (
+                Day3ScanState::FirstNum(day3_push_digit(num, byte), digits + 1),
+                None,
+            ),
+            (Day3ScanState::FirstNum(num, digits), b',') if digits > 0 => {
+                (Day3ScanState::SecondNum((num, digits), Some(0), 0), None)
+            }
+            (Day3ScanState::SecondNum(first_num, num, digits), b'0'..=b'9') => (
+                Day3ScanState::SecondNum(first_num, day3_push_digit(num, byte), digits + 1),
+                None,
+            ),
+            (Day3ScanState::SecondNum((first_num, first_digits), num, digits), b')')
+                if digits > 0 =>
+            {
+                (
+                    Day3ScanState::Idle,
+                    self.check_mul(first_num, first_digits, num, digits),
+                )
+            }
+            // No instruction has a proper prefix that ends in the start of another instruction,
+            // so on a mismatch the only place a new instruction can start is this byte.
+            (_, b'm') => {
+                self.instruction_start = self.offset;
+                (Day3ScanState::Literal(b"mul(", 1), None)
+            }
+            (_, b'd') => {
+                self.instruction_start = self.offset;
+                (Day3ScanState::Literal(b"do()", 1), None)
+            }
+            _ => (Day3ScanState::Idle, None),
+        };
+        self.state = next_state;
+        instruction
+    }
 }
 
-enum Day6Direction {
-    Up,
-    Right,
-    Down,
-    Left,
+fn day3_push_digit(num: Option<u64>, digit: u8) -> Option<u64> {
+    num?.checked_mul(10)?.checked_add((digit - b'0') as u64)
 }
 
-pub fn day6_part1(inp: &str) -> usize {
-    let grid = inp
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect::<Vec<Vec<_>>>();
-    let rows = grid.len();
-    let cols = grid[0].len();
-    let transp_grid = (0..cols)
-        .map(|col| (0..rows).map(|row| grid[row][col]).collect())
-        .collect::<Vec<Vec<_>>>();
-    let mut guard_loc = (0, 0);
-    'outer: for (row_num, row) in grid.iter().enumerate() {
-        for (col_num, &col) in row.iter().enumerate() {
-            if col == '^' {
-                guard_loc = (col_num, row_num);
-                break 'outer;
-            }
+pub fn day4_part1(inp: &str) -> u64 {
+    WordSearch::new(inp).find("XMAS").len().try_into().unwrap()
+}
+
+pub fn day4_part2(inp: &str) -> usize {
+    let x_mas = GridPattern::new("M.S\n.A.\nM.S", '.');
+    Grid::new(inp).find_pattern(&x_mas, true).len()
+}
+
+// Shows only the letters that are part of an XMAS, as in the puzzle description.
+pub fn day4_part1_highlighted(inp: &str, style: HighlightStyle) -> String {
+    let word_search = WordSearch::new(inp);
+    let cells = word_search
+        .find("XMAS")
+        .into_iter()
+        .flat_map(|word_match| (0..4).map(move |n| word_match.position_of_letter(n)))
+        .collect();
+    word_search.grid().render_highlighted(&cells, style)
+}
+
+// Shows only the letters that are part of an X-MAS, as in the puzzle description.
+pub fn day4_part2_highlighted(inp: &str, style: HighlightStyle) -> String {
+    let grid = Grid::new(inp);
+    let x_mas = GridPattern::new("M.S\n.A.\nM.S", '.');
+    let cells = grid
+        .find_pattern(&x_mas, true)
+        .iter()
+        .flat_map(|pattern_match| pattern_match.cells())
+        .collect();
+    grid.render_highlighted(&cells, style)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HighlightStyle {
+    // Other cells are replaced by '.'.
+    Text,
+    // Highlighted cells are bold green and other cells are dimmed, for viewing in a terminal.
+    Ansi,
+}
+
+// A grid of characters, one row per line of input.
+#[derive(Debug, Clone)]
+pub struct Grid {
+    rows: Vec<Vec<char>>,
+}
+
+impl Grid {
+    pub fn new(inp: &str) -> Self {
+        Grid {
+            rows: inp.lines().map(|line| line.chars().collect()).collect(),
         }
     }
-    let mut guard_direction = Day6Direction::Up;
-    let mut visited_positions = HashSet::new();
-    visited_positions.insert(guard_loc);
-    loop {
-        // Below code may have some +-1 errors, but it worked well enough to get the right answer for the inputs I had.
-        match guard_direction {
-            Day6Direction::Up => {
+
+    pub fn width(&self) -> usize {
+        self.rows.first().map_or(0, |row| row.len())
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    // Returns None for points outside the grid, so callers can step off the edge freely.
+    pub fn get(&self, (x, y): (i64, i64)) -> Option<char> {
+        let row = self.rows.get(usize::try_from(y).ok()?)?;
+        row.get(usize::try_from(x).ok()?).copied()
+    }
+
+    // All points in the grid, in reading order.
+    pub fn points(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.rows
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| (0..row.len()).map(move |x| (x, y)))
+    }
+
+    pub fn render_highlighted(
+        &self,
+        cells: &HashSet<(usize, usize)>,
+        style: HighlightStyle,
+    ) -> String {
+        self.rows
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .map(|(x, &chr)| match (style, cells.contains(&(x, y))) {
+                        (HighlightStyle::Text, true) => chr.to_string(),
+                        (HighlightStyle::Text, false) => ".".to_string(),
+                        (HighlightStyle::Ansi, true) => format!("\x1b[1;32m{chr}\x1b[0m"),
+                        (HighlightStyle::Ansi, false) => format!("\x1b[2m{chr}\x1b[0m"),
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // Finds every place the pattern matches, in reading order. If any_orientation is set, every
+    // rotation and reflection of the pattern is tried too.
+    pub fn find_pattern(&self, pattern: &GridPattern, any_orientation: bool) -> Vec<PatternMatch> {
+        let orientations = if any_orientation {
+            pattern.orientations()
+        } else {
+            vec![pattern.clone()]
+        };
+        let mut matches = Vec::new();
+        for top_left @ (x, y) in self.points() {
+            for pattern in &orientations {
+                if x + pattern.width() > self.width() || y + pattern.height() > self.height() {
+                    continue;
+                }
+                let is_match = pattern
+                    .fixed_cells()
+                    .all(|((dx, dy), c)| self.get(((x + dx) as i64, (y + dy) as i64)) == Some(c));
+                if is_match {
+                    matches.push(PatternMatch {
+                        top_left,
+                        pattern: pattern.clone(),
+                    });
+                }
+            }
+        }
+        matches
+    }
+}
+
+// A small grid of characters to look for in a bigger grid. Wildcard cells (None) match
+// anything.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct GridPattern {
+    rows: Vec<Vec<Option<char>>>,
+}
+
+impl GridPattern {
+    pub fn new(pattern: &str, wildcard: char) -> Self {
+        GridPattern {
+            rows: pattern
+                .lines()
+                .map(|line| line.chars().map(|c| (c != wildcard).then_some(c)).collect())
+                .collect(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.rows.iter().map(|row| row.len()).max().unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    // The pattern turned 90 degrees clockwise. Short rows are padded with wildcards.
+    pub fn rotated(&self) -> Self {
+        let height = self.height();
+        GridPattern {
+            rows: (0..self.width())
+                .map(|x| {
+                    (0..height)
+                        .rev()
+                        .map(|y| self.rows[y].get(x).copied().flatten())
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    // The pattern mirrored left to right.
+    pub fn reflected(&self) -> Self {
+        let width = self.width();
+        GridPattern {
+            rows: self
+                .rows
+                .iter()
+                .map(|row| {
+                    (0..width)
+                        .rev()
+                        .map(|x| row.get(x).copied().flatten())
+                        .collect()
+                })
+                .collect(),
+        }
+    }
+
+    // Every distinct rotation and reflection of the pattern, starting with the pattern itself.
+    pub fn orientations(&self) -> Vec<GridPattern> {
+        let mut orientations: Vec<GridPattern> = Vec::new();
+        for mut pattern in [self.clone(), self.reflected()] {
+            for _ in 0..4 {
+                if !orientations.contains(&pattern) {
+                    orientations.push(pattern.clone());
+                }
+                pattern = pattern.rotated();
+            }
+        }
+        orientations
+    }
+
+    // Non-wildcard cells, as (x, y) offsets from the top left of the pattern.
+    pub fn fixed_cells(&self) -> impl Iterator<Item = ((usize, usize), char)> + '_ {
+        self.rows.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter_map(move |(x, &cell)| cell.map(|c| ((x, y), c)))
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternMatch {
+    pub top_left: (usize, usize),
+    // The orientation of the pattern that matched.
+    pub pattern: GridPattern,
+}
+
+impl PatternMatch {
+    // Grid positions of the matched non-wildcard cells.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.pattern
+            .fixed_cells()
+            .map(|((x, y), _)| (self.top_left.0 + x, self.top_left.1 + y))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Day4Direction {
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+}
+
+impl Day4Direction {
+    pub const ALL: [Day4Direction; 8] = [
+        Day4Direction::Up,
+        Day4Direction::UpRight,
+        Day4Direction::Right,
+        Day4Direction::DownRight,
+        Day4Direction::Down,
+        Day4Direction::DownLeft,
+        Day4Direction::Left,
+        Day4Direction::UpLeft,
+    ];
+
+    // (x, y) change for one step in this direction. y increases going down.
+    pub fn offset(self) -> (i64, i64) {
+        match self {
+            Day4Direction::Up => (0, -1),
+            Day4Direction::UpRight => (1, -1),
+            Day4Direction::Right => (1, 0),
+            Day4Direction::DownRight => (1, 1),
+            Day4Direction::Down => (0, 1),
+            Day4Direction::DownLeft => (-1, 1),
+            Day4Direction::Left => (-1, 0),
+            Day4Direction::UpLeft => (-1, -1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WordMatch {
+    pub start: (usize, usize),
+    pub direction: Day4Direction,
+}
+
+impl WordMatch {
+    // Position of the nth letter of the matched word.
+    pub fn position_of_letter(&self, n: usize) -> (usize, usize) {
+        let (dx, dy) = self.direction.offset();
+        (
+            self.start
+                .0
+                .checked_add_signed(dx as isize * n as isize)
+                .unwrap(),
+            self.start
+                .1
+                .checked_add_signed(dy as isize * n as isize)
+                .unwrap(),
+        )
+    }
+}
+
+pub struct WordSearch {
+    grid: Grid,
+}
+
+impl WordSearch {
+    pub fn new(inp: &str) -> Self {
+        WordSearch {
+            grid: Grid::new(inp),
+        }
+    }
+
+    pub fn grid(&self) -> &Grid {
+        &self.grid
+    }
+
+    // Finds every occurrence of the word in any of the 8 directions, in reading order of the
+    // start position.
+    pub fn find(&self, word: &str) -> Vec<WordMatch> {
+        self.find_in_directions(word, &Day4Direction::ALL)
+    }
+
+    // A single letter reads the same in every direction, so it's only reported once per cell, in
+    // the first direction given.
+    pub fn find_in_directions(&self, word: &str, directions: &[Day4Direction]) -> Vec<WordMatch> {
+        let word = word.chars().collect::<Vec<_>>();
+        let Some(&first_letter) = word.first() else {
+            return Vec::new();
+        };
+        let directions = if word.len() == 1 {
+            &directions[..1.min(directions.len())]
+        } else {
+            directions
+        };
+        let mut matches = Vec::new();
+        for start @ (x, y) in self.grid.points() {
+            if self.grid.get((x as i64, y as i64)) != Some(first_letter) {
+                continue;
+            }
+            for &direction in directions {
+                let (dx, dy) = direction.offset();
+                let is_match = word.iter().enumerate().skip(1).all(|(n, &letter)| {
+                    let n = n as i64;
+                    self.grid.get((x as i64 + dx * n, y as i64 + dy * n)) == Some(letter)
+                });
+                if is_match {
+                    matches.push(WordMatch { start, direction });
+                }
+            }
+        }
+        matches
+    }
+
+    // Finds every occurrence of every word in the dictionary, walking a trie of the words out
+    // from each cell in each direction, so the grid is only scanned once however many words
+    // there are. As with `find`, single letter words are only reported once per cell.
+    pub fn find_words<'a>(&self, words: impl IntoIterator<Item = &'a str>) -> DictionaryMatches {
+        let trie = WordTrie::new(words);
+        let mut matches = Vec::new();
+        for &direction in &Day4Direction::ALL {
+            let (dx, dy) = direction.offset();
+            for start @ (x, y) in self.grid.points() {
+                let mut node = 0;
+                let mut position = (x as i64, y as i64);
+                while let Some(letter) = self.grid.get(position) {
+                    let Some(&next_node) = trie.nodes[node].children.get(&letter) else {
+                        break;
+                    };
+                    node = next_node;
+                    if let Some(word) = &trie.nodes[node].word {
+                        if position != (x as i64, y as i64) || direction == Day4Direction::ALL[0] {
+                            matches.push((word.clone(), WordMatch { start, direction }));
+                        }
+                    }
+                    position = (position.0 + dx, position.1 + dy);
+                }
+            }
+        }
+        matches.sort_by_key(|(_, word_match)| (word_match.start.1, word_match.start.0));
+
+        let mut cell_usage = HashMap::new();
+        for (word, word_match) in &matches {
+            for n in 0..word.chars().count() {
+                *cell_usage
+                    .entry(word_match.position_of_letter(n))
+                    .or_insert(0) += 1;
+            }
+        }
+        DictionaryMatches {
+            matches,
+            cell_usage,
+        }
+    }
+}
+
+#[derive(Default)]
+struct WordTrieNode {
+    children: HashMap<char, usize>,
+    // Set if a word ends at this node.
+    word: Option<String>,
+}
+
+// Nodes are stored in a Vec and refer to their children by index. The root is node 0.
+struct WordTrie {
+    nodes: Vec<WordTrieNode>,
+}
+
+impl WordTrie {
+    fn new<'a>(words: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut nodes = vec![WordTrieNode::default()];
+        for word in words {
+            let mut node = 0;
+            for letter in word.chars() {
+                node = match nodes[node].children.get(&letter) {
+                    Some(&child) => child,
+                    None => {
+                        nodes.push(WordTrieNode::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.insert(letter, child);
+                        child
+                    }
+                };
+            }
+            if node != 0 {
+                nodes[node].word = Some(word.to_string());
+            }
+        }
+        WordTrie { nodes }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DictionaryMatches {
+    // Every match, in reading order of the start position.
+    pub matches: Vec<(String, WordMatch)>,
+    // Number of matches using each cell.
+    pub cell_usage: HashMap<(usize, usize), usize>,
+}
+
+impl DictionaryMatches {
+    pub fn count(&self, word: &str) -> usize {
+        self.matches.iter().filter(|(w, _)| w == word).count()
+    }
+
+    // Number of cells used by more than one match.
+    pub fn overlapping_cells(&self) -> usize {
+        self.cell_usage.values().filter(|&&usage| usage > 1).count()
+    }
+}
+
+pub fn day5_part1(inp: &str) -> u32 {
+    let (first_part, second_part) = inp.split_once("\n\n").unwrap();
+    let page_ordering_rules = first_part
+        .lines()
+        .map(|line| {
+            let (a, b) = line.split_once('|').unwrap();
+            (a.parse().unwrap(), b.parse().unwrap())
+        })
+        .collect::<HashSet<(u32, u32)>>();
+
+    second_part
+        .lines()
+        .map(|line| {
+            line.split(',')
+                .map(|string| string.parse().unwrap())
+                .collect::<Vec<u32>>()
+        })
+        .filter(|update| {
+            for (n, &a) in update.iter().enumerate() {
+                for &b in update.iter().skip(n + 1) {
+                    if !page_ordering_rules.contains(&(a, b)) {
+                        return false;
+                    }
+                }
+            }
+            true
+        })
+        .map(|update| update[update.len() / 2])
+        .sum()
+}
+
+pub fn day5_part2(inp: &str) -> u32 {
+    let (first_part, second_part) = inp.split_once("\n\n").unwrap();
+    let page_ordering_rules = first_part
+        .lines()
+        .map(|line| {
+            let (a, b) = line.split_once('|').unwrap();
+            (a.parse().unwrap(), b.parse().unwrap())
+        })
+        .collect::<HashSet<(u32, u32)>>();
+
+    second_part
+        .lines()
+        .map(|line| {
+            line.split(',')
+                .map(|string| string.parse().unwrap())
+                .collect::<Vec<u32>>()
+        })
+        .filter(|update| {
+            for (n, &a) in update.iter().enumerate() {
+                for &b in update.iter().skip(n + 1) {
+                    if !page_ordering_rules.contains(&(a, b)) {
+                        return true;
+                    }
+                }
+            }
+            false
+        })
+        .map(|update| {
+            let mut new_update = Vec::new();
+            for page_num in update.into_iter() {
+                let mut havent_inserted = true;
+                for (n, &page_num1) in new_update.iter().enumerate() {
+                    if page_ordering_rules.contains(&(page_num1, page_num)) {
+                        continue;
+                    } else {
+                        new_update.insert(n, page_num);
+                        havent_inserted = false;
+                        break;
+                    }
+                }
+                if havent_inserted {
+                    new_update.push(page_num);
+                }
+            }
+            new_update[new_update.len() / 2]
+        })
+        .sum()
+}
+
+enum Day6Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+pub fn day6_part1(inp: &str) -> usize {
+    let grid = inp
+        .lines()
+        .map(|line| line.chars().collect())
+        .collect::<Vec<Vec<_>>>();
+    let rows = grid.len();
+    let cols = grid[0].len();
+    let transp_grid = (0..cols)
+        .map(|col| (0..rows).map(|row| grid[row][col]).collect())
+        .collect::<Vec<Vec<_>>>();
+    let mut guard_loc = (0, 0);
+    'outer: for (row_num, row) in grid.iter().enumerate() {
+        for (col_num, &col) in row.iter().enumerate() {
+            if col == '^' {
+                guard_loc = (col_num, row_num);
+                break 'outer;
+            }
+        }
+    }
+    let mut guard_direction = Day6Direction::Up;
+    let mut visited_positions = HashSet::new();
+    visited_positions.insert(guard_loc);
+    loop {
+        // Below code may have some +-1 errors, but it worked well enough to get the right answer for the inputs I had.
+        match guard_direction {
+            Day6Direction::Up => {
                 let col_part = &transp_grid[guard_loc.0][0..guard_loc.1];
                 match col_part.iter().rposition(|&chr| chr == '#') {
                     Some(wall_col_num) => {
@@ -766,214 +1453,1970 @@ pub fn day9_part1(inp: &str) -> usize {
         if file_block == -1 {
             continue;
         }
-        // could optimise by skipping ahead and not always starting at the beginning.
-        // But this implementation is fast enough in release mode.
-        let index = disk.iter().position(|f| *f == -1).unwrap();
-        disk[index] = file_block;
+        // could optimise by skipping ahead and not always starting at the beginning.
+        // But this implementation is fast enough in release mode.
+        let index = disk.iter().position(|f| *f == -1).unwrap();
+        disk[index] = file_block;
+    }
+    disk.iter()
+        .enumerate()
+        .map(|(n, &file_id)| n * file_id as usize)
+        .sum()
+}
+
+pub fn day9_part2(inp: &str) -> usize {
+    let mut disk: Vec<i32> = Vec::new();
+    // vec of (start_pos_in_disk, length_of_file). Position in this vec is the file ID.
+    let mut files = Vec::new();
+    for (n, digit) in inp
+        .chars()
+        .filter(|c| c != &'\n')
+        .map(|c| c.to_digit(10).unwrap())
+        .enumerate()
+    {
+        if n % 2 == 0 {
+            files.push((disk.len(), digit));
+            disk.append(&mut vec![(n / 2) as i32; digit as usize]);
+        } else {
+            disk.append(&mut vec![-1; digit as usize]);
+        }
+    }
+    for (file_id, &(file_start_pos, file_length)) in files.iter().enumerate().skip(1).rev() {
+        for (n, window) in disk.windows(file_length as usize).enumerate() {
+            if n >= file_start_pos {
+                break;
+            }
+            if window.iter().all(|&f| f == -1) {
+                for item in disk.iter_mut().skip(n).take(file_length as usize) {
+                    *item = file_id as i32;
+                }
+                for item in disk
+                    .iter_mut()
+                    .skip(file_start_pos)
+                    .take(file_length as usize)
+                {
+                    *item = -1;
+                }
+                while disk[disk.len() - 1] == -1 {
+                    disk.pop();
+                }
+                break;
+            }
+        }
+    }
+    disk.iter()
+        .enumerate()
+        .map(|(n, &file_id)| {
+            if file_id == -1 {
+                0
+            } else {
+                n * file_id as usize
+            }
+        })
+        .sum()
+}
+
+// Rules for what counts as a hiking trail. The defaults are the ones from the puzzle. Heights
+// in the map are single digits, so only heights 0 to 9 can be used.
+#[derive(Debug, Clone)]
+pub struct TrailRules {
+    pub start_height: u32,
+    pub end_height: u32,
+    // Allowed height increases for a single step. Must all be at least 1, otherwise trails could
+    // go round in circles.
+    pub climb_deltas: Vec<u32>,
+    pub diagonal_moves: bool,
+    // Characters in the map that can't be walked on.
+    pub impassable: Vec<char>,
+}
+
+impl Default for TrailRules {
+    fn default() -> Self {
+        TrailRules {
+            start_height: 0,
+            end_height: 9,
+            climb_deltas: vec![1],
+            diagonal_moves: false,
+            impassable: vec!['.'],
+        }
+    }
+}
+
+pub fn day10_part1(inp: &str) -> usize {
+    day10_total_score(inp, &TrailRules::default()).unwrap()
+}
+
+pub fn day10_part2(inp: &str) -> usize {
+    day10_total_rating(inp, &TrailRules::default()).unwrap()
+}
+
+pub fn day10_total_score(inp: &str, rules: &TrailRules) -> Result<usize, String> {
+    let grid = day10_parse_grid(inp, rules)?;
+    let scores = day10_compute_all_scores(&grid, rules);
+    Ok(day10_sum_over_trailheads(&grid, &scores, rules))
+}
+
+pub fn day10_total_rating(inp: &str, rules: &TrailRules) -> Result<usize, String> {
+    let grid = day10_parse_grid(inp, rules)?;
+    let ratings = day10_compute_all_ratings(&grid, rules);
+    Ok(day10_sum_over_trailheads(&grid, &ratings, rules))
+}
+
+// (trailhead, score, rating)
+pub type TrailheadSummary = ((usize, usize), usize, usize);
+
+// Returns the summary of every trailhead, in reading order.
+pub fn day10_trailhead_summaries(
+    inp: &str,
+    rules: &TrailRules,
+) -> Result<Vec<TrailheadSummary>, String> {
+    let grid = day10_parse_grid(inp, rules)?;
+    let scores = day10_compute_all_scores(&grid, rules);
+    let ratings = day10_compute_all_ratings(&grid, rules);
+    let mut summaries = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        for (x, &height) in row.iter().enumerate() {
+            if height == Some(rules.start_height) {
+                summaries.push(((x, y), scores[y][x], ratings[y][x]));
+            }
+        }
+    }
+    Ok(summaries)
+}
+
+// Returns every hiking trail starting at the given trailhead, as the sequence of points
+// visited from the start height to the end height. Returns no trails if the point isn't a
+// trailhead.
+pub fn day10_trails_from(
+    inp: &str,
+    trailhead @ (x, y): (usize, usize),
+    rules: &TrailRules,
+) -> Result<Vec<Vec<(usize, usize)>>, String> {
+    let grid = day10_parse_grid(inp, rules)?;
+    let mut trails = Vec::new();
+    if grid.get(y).and_then(|row| row.get(x)) == Some(&Some(rules.start_height)) {
+        day10_collect_trails(&mut vec![trailhead], &grid, rules, &mut trails);
+    }
+    Ok(trails)
+}
+
+// Renders the map with every point not on the trail replaced by '.'.
+pub fn day10_render_trail(inp: &str, trail: &[(usize, usize)]) -> String {
+    let trail_points = trail.iter().collect::<HashSet<_>>();
+    inp.lines()
+        .enumerate()
+        .map(|(y, line)| {
+            line.chars()
+                .enumerate()
+                .map(|(x, chr)| {
+                    if trail_points.contains(&(x, y)) {
+                        chr
+                    } else {
+                        '.'
+                    }
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Impassable points are None. Also checks the rules, as everything that uses them parses the map
+// first.
+fn day10_parse_grid(inp: &str, rules: &TrailRules) -> Result<Vec<Vec<Option<u32>>>, String> {
+    if rules.climb_deltas.contains(&0) {
+        return Err("climb deltas must all be at least 1".to_string());
+    }
+    inp.lines()
+        .enumerate()
+        .map(|(line_num, line)| {
+            line.chars()
+                .map(|c| {
+                    if rules.impassable.contains(&c) {
+                        Ok(None)
+                    } else {
+                        c.to_digit(10)
+                            .map(Some)
+                            .ok_or_else(|| format!("line {}: invalid height {c:?}", line_num + 1))
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn day10_collect_trails(
+    trail: &mut Vec<(usize, usize)>,
+    grid: &[Vec<Option<u32>>],
+    rules: &TrailRules,
+    trails: &mut Vec<Vec<(usize, usize)>>,
+) {
+    let &position @ (x, y) = trail.last().unwrap();
+    if grid[y][x] == Some(rules.end_height) {
+        trails.push(trail.clone());
+        return;
+    }
+    for point in day10_get_next_steps(position, grid, rules) {
+        trail.push(point);
+        day10_collect_trails(trail, grid, rules, trails);
+        trail.pop();
+    }
+}
+
+fn day10_sum_over_trailheads(
+    grid: &[Vec<Option<u32>>],
+    values: &[Vec<usize>],
+    rules: &TrailRules,
+) -> usize {
+    grid.iter()
+        .zip(values)
+        .flat_map(|(row, values_row)| row.iter().zip(values_row))
+        .filter(|(&height, _)| height == Some(rules.start_height))
+        .map(|(_, &value)| value)
+        .sum()
+}
+
+// Groups the passable points in the given area by height, ignoring anything above the end height.
+fn day10_group_by_height(
+    grid: &[Vec<Option<u32>>],
+    xs: std::ops::Range<usize>,
+    ys: std::ops::Range<usize>,
+    rules: &TrailRules,
+) -> Vec<Vec<(usize, usize)>> {
+    let mut cells_by_height = vec![Vec::new(); rules.end_height as usize + 1];
+    for y in ys {
+        for x in xs.clone() {
+            if let Some(height) = grid[y][x].filter(|&height| height <= rules.end_height) {
+                cells_by_height[height as usize].push((x, y));
+            }
+        }
+    }
+    cells_by_height
+}
+
+// Rating of every cell, computed bottom-up from the end height so shared subpaths are only
+// walked once. Steps always climb, so every cell a step leads to has already been rated.
+fn day10_compute_all_ratings(grid: &[Vec<Option<u32>>], rules: &TrailRules) -> Vec<Vec<usize>> {
+    if grid.is_empty() {
+        return Vec::new();
+    }
+    let cells_by_height = day10_group_by_height(grid, 0..grid[0].len(), 0..grid.len(), rules);
+    let mut ratings = vec![vec![0; grid[0].len()]; grid.len()];
+    for &(x, y) in &cells_by_height[rules.end_height as usize] {
+        ratings[y][x] = 1;
+    }
+    for cells in cells_by_height.iter().rev().skip(1) {
+        for &position @ (x, y) in cells {
+            ratings[y][x] = day10_get_next_steps(position, grid, rules)
+                .map(|(x1, y1)| ratings[y1][x1])
+                .sum();
+        }
+    }
+    ratings
+}
+
+// Score of every cell. The summits are handled one 8x8 tile at a time, so the set of reachable
+// summits fits in a u64 bitset per cell, and only cells close enough to climb to the tile need
+// visiting.
+fn day10_compute_all_scores(grid: &[Vec<Option<u32>>], rules: &TrailRules) -> Vec<Vec<usize>> {
+    if grid.is_empty() {
+        return Vec::new();
+    }
+    let height = grid.len();
+    let width = grid[0].len();
+    // every step climbs by at least 1, so no trail is longer than this
+    let max_trail_steps = rules.end_height.saturating_sub(rules.start_height) as usize;
+    let mut scores = vec![vec![0; width]; height];
+    let mut reachable_summits = vec![vec![0_u64; width]; height];
+    for tile_y in (0..height).step_by(8) {
+        for tile_x in (0..width).step_by(8) {
+            let mut found_summit = false;
+            for y in tile_y..(tile_y + 8).min(height) {
+                for x in tile_x..(tile_x + 8).min(width) {
+                    if grid[y][x] == Some(rules.end_height) {
+                        reachable_summits[y][x] = 1 << ((y - tile_y) * 8 + (x - tile_x));
+                        scores[y][x] = 1;
+                        found_summit = true;
+                    }
+                }
+            }
+            if !found_summit {
+                continue;
+            }
+            let xs =
+                tile_x.saturating_sub(max_trail_steps)..(tile_x + 8 + max_trail_steps).min(width);
+            let ys =
+                tile_y.saturating_sub(max_trail_steps)..(tile_y + 8 + max_trail_steps).min(height);
+            let cells_by_height = day10_group_by_height(grid, xs.clone(), ys.clone(), rules);
+            for cells in cells_by_height.iter().rev().skip(1) {
+                for &position @ (x, y) in cells {
+                    let bits = day10_get_next_steps(position, grid, rules)
+                        .fold(0, |acc, (x1, y1)| acc | reachable_summits[y1][x1]);
+                    reachable_summits[y][x] = bits;
+                    scores[y][x] += bits.count_ones() as usize;
+                }
+            }
+            // reset the area around this tile, ready for the next one
+            for y in ys {
+                reachable_summits[y][xs.clone()].fill(0);
+            }
+        }
+    }
+    scores
+}
+
+// The points that can be stepped to from the given point, without going above the end height.
+fn day10_get_next_steps<'a>(
+    position: (usize, usize),
+    grid: &'a [Vec<Option<u32>>],
+    rules: &'a TrailRules,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let current_height = grid[position.1][position.0].unwrap();
+    day10_get_surrounding_points(position, grid, rules.diagonal_moves).filter(move |&(x1, y1)| {
+        grid[y1][x1].is_some_and(|height| {
+            height <= rules.end_height
+                && height
+                    .checked_sub(current_height)
+                    .is_some_and(|delta| rules.climb_deltas.contains(&delta))
+        })
+    })
+}
+
+fn day10_get_surrounding_points<T>(
+    (x, y): (usize, usize),
+    grid: &[Vec<T>],
+    diagonal_moves: bool,
+) -> impl Iterator<Item = (usize, usize)> {
+    let (x, y) = (x as i32, y as i32);
+    let height = grid.len() as i32;
+    let width = grid.first().map_or(0, Vec::len) as i32;
+    let num_of_directions = if diagonal_moves { 8 } else { 4 };
+    [
+        (x + 1, y),
+        (x - 1, y),
+        (x, y + 1),
+        (x, y - 1),
+        (x + 1, y + 1),
+        (x + 1, y - 1),
+        (x - 1, y + 1),
+        (x - 1, y - 1),
+    ]
+    .into_iter()
+    .take(num_of_directions)
+    .filter(move |(x1, y1)| *x1 >= 0 && *y1 >= 0 && *x1 < width && *y1 < height)
+    .map(|(x1, y1)| (x1 as usize, y1 as usize))
+}
+
+pub fn day11_part1(inp: &str) -> usize {
+    StoneEvolver::new(inp)
+        .count_after(25)
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+pub fn day11_part2(inp: &str) -> u64 {
+    StoneEvolver::new(inp)
+        .count_after(75)
+        .unwrap()
+        .try_into()
+        .unwrap()
+}
+
+// A single way a stone can change when blinked at. Rules are tried in order, and the first one
+// that applies decides what the stone turns into.
+#[derive(Debug, Clone)]
+pub enum StoneRule {
+    // A stone engraved with `from` becomes a stone engraved with `to`.
+    Replace { from: u64, to: u64 },
+    // A stone whose number of digits in `base` is a multiple of `parts` is split into that many
+    // stones, each taking an equal share of the digits, most significant first. `base` must be at
+    // least 2 and `parts` at least 1.
+    Split { base: u64, parts: u32 },
+    // A stone is multiplied by this amount.
+    Multiply(u64),
+    // Returns None if the rule doesn't apply to the stone.
+    Custom(fn(u64) -> Option<Vec<u64>>),
+}
+
+impl StoneRule {
+    // The rules from the puzzle.
+    pub fn puzzle_rules() -> Vec<StoneRule> {
+        vec![
+            StoneRule::Replace { from: 0, to: 1 },
+            StoneRule::Split { base: 10, parts: 2 },
+            StoneRule::Multiply(2024),
+        ]
+    }
+
+    // Checks that the rule's parameters make sense.
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            StoneRule::Split { base, .. } if base < 2 => {
+                Err(format!("split base must be at least 2, got {base}"))
+            }
+            StoneRule::Split { parts: 0, .. } => Err("split needs at least 1 part".to_string()),
+            _ => Ok(()),
+        }
+    }
+
+    // The rule should have been checked with `validate` first.
+    pub fn apply(&self, stone: u64) -> Option<Vec<u64>> {
+        match *self {
+            StoneRule::Replace { from, to } => (stone == from).then(|| vec![to]),
+            StoneRule::Split { base, parts } => {
+                let num_of_digits = stone.checked_ilog(base).unwrap_or(0) + 1;
+                if !num_of_digits.is_multiple_of(parts) {
+                    return None;
+                }
+                // with a single part this is more than the stone, which might not fit in a u64
+                let part_size = (base as u128).pow(num_of_digits / parts);
+                let mut stones = (0..parts)
+                    .scan(stone as u128, |remaining, _| {
+                        let part = *remaining % part_size;
+                        *remaining /= part_size;
+                        Some(part as u64)
+                    })
+                    .collect::<Vec<_>>();
+                stones.reverse();
+                Some(stones)
+            }
+            StoneRule::Multiply(multiplier) => Some(vec![stone
+                .checked_mul(multiplier)
+                .expect("stone value overflowed u64")]),
+            StoneRule::Custom(rule) => rule(stone),
+        }
+    }
+}
+
+// The stones present after a number of blinks.
+#[derive(Debug, Clone)]
+pub struct StonePopulation {
+    pub blink: usize,
+    pub distinct_values: usize,
+    pub largest_value: u64,
+    // Number of stones engraved with each value.
+    pub histogram: BTreeMap<u64, u128>,
+}
+
+// Counts stones after any number of blinks, memoising the count for each (stone, blinks
+// remaining) pair so that repeated stones are only ever expanded once.
+pub struct StoneEvolver {
+    initial_stones: Vec<u64>,
+    // Stones that no rule applies to are left unchanged.
+    rules: Vec<StoneRule>,
+    // If set, counts are reported modulo this, so very large blink counts don't overflow.
+    modulus: Option<u128>,
+    cache: HashMap<(u64, usize), u128>,
+}
+
+impl StoneEvolver {
+    pub fn new(inp: &str) -> Self {
+        StoneEvolver {
+            initial_stones: inp
+                .split_ascii_whitespace()
+                .map(|s| s.parse().unwrap())
+                .collect(),
+            rules: StoneRule::puzzle_rules(),
+            modulus: None,
+            cache: HashMap::new(),
+        }
+    }
+
+    // Fails if any of the rules aren't valid.
+    pub fn with_rules(mut self, rules: Vec<StoneRule>) -> Result<Self, String> {
+        for rule in &rules {
+            rule.validate()?;
+        }
+        self.rules = rules;
+        self.cache.clear();
+        Ok(self)
+    }
+
+    pub fn with_modulus(mut self, modulus: u128) -> Self {
+        assert!(modulus > 0, "modulus must be positive");
+        self.modulus = Some(modulus);
+        self.cache.clear();
+        self
+    }
+
+    // Returns None if the count doesn't fit in a u128. Use `with_modulus` to count that far.
+    pub fn count_after(&mut self, blinks: usize) -> Option<u128> {
+        let mut total = 0;
+        for stone in self.initial_stones.clone() {
+            let count = self.count_stone(stone, blinks)?;
+            total = self.add_counts(total, count)?;
+        }
+        Some(total)
+    }
+
+    // Returns the count after 0, 1, ..., blinks blinks.
+    pub fn counts_per_blink(&mut self, blinks: usize) -> Vec<Option<u128>> {
+        (0..=blinks).map(|n| self.count_after(n)).collect()
+    }
+
+    // Returns the population after 0, 1, ..., blinks blinks. Unlike the counts, this tracks
+    // every distinct value, so it's only practical while the set of values stays small.
+    pub fn populations(&self, blinks: usize) -> Vec<StonePopulation> {
+        let mut histogram = BTreeMap::new();
+        for &stone in &self.initial_stones {
+            let count = histogram.get(&stone).copied().unwrap_or(0);
+            histogram.insert(stone, self.add_counts(count, 1).unwrap());
+        }
+        let mut populations = Vec::new();
+        for blink in 0..=blinks {
+            if blink > 0 {
+                histogram = self.blink_histogram(&histogram);
+            }
+            populations.push(StonePopulation {
+                blink,
+                distinct_values: histogram.len(),
+                largest_value: histogram.keys().next_back().copied().unwrap_or(0),
+                histogram: histogram.clone(),
+            });
+        }
+        populations
+    }
+
+    // Finds the closed orbit: the set of every value ever seen, once blinking stops adding new
+    // values to it. If one blink adds no new values, none of the following blinks can either.
+    // Returns the blink at which it stopped growing and the set, or None if it was still growing
+    // after max_blinks.
+    pub fn closed_orbit(&self, max_blinks: usize) -> Option<(usize, BTreeSet<u64>)> {
+        let mut seen_values = self.initial_stones.iter().copied().collect::<BTreeSet<_>>();
+        let mut current_values = seen_values.clone();
+        for blink in 1..=max_blinks {
+            current_values = current_values
+                .into_iter()
+                .flat_map(|stone| self.blink_stone(stone))
+                .collect();
+            let num_of_seen_values = seen_values.len();
+            seen_values.extend(&current_values);
+            if seen_values.len() == num_of_seen_values {
+                return Some((blink, seen_values));
+            }
+        }
+        None
+    }
+
+    fn blink_histogram(&self, histogram: &BTreeMap<u64, u128>) -> BTreeMap<u64, u128> {
+        let mut new_histogram = BTreeMap::new();
+        for (&stone, &count) in histogram {
+            for new_stone in self.blink_stone(stone) {
+                let new_count = new_histogram.get(&new_stone).copied().unwrap_or(0);
+                let new_count = self
+                    .add_counts(new_count, count)
+                    .expect("stone count overflowed u128, use with_modulus");
+                new_histogram.insert(new_stone, new_count);
+            }
+        }
+        new_histogram
+    }
+
+    fn count_stone(&mut self, stone: u64, blinks: usize) -> Option<u128> {
+        if blinks == 0 {
+            return Some(1);
+        }
+        if let Some(&count) = self.cache.get(&(stone, blinks)) {
+            return Some(count);
+        }
+        let mut count = 0;
+        for new_stone in self.blink_stone(stone) {
+            let new_stone_count = self.count_stone(new_stone, blinks - 1)?;
+            count = self.add_counts(count, new_stone_count)?;
+        }
+        self.cache.insert((stone, blinks), count);
+        Some(count)
+    }
+
+    // Returns the stones a single stone turns into after one blink.
+    pub fn blink_stone(&self, stone: u64) -> Vec<u64> {
+        self.rules
+            .iter()
+            .find_map(|rule| rule.apply(stone))
+            .unwrap_or_else(|| vec![stone])
+    }
+
+    // Returns None on overflow, which can only happen without a modulus.
+    fn add_counts(&self, a: u128, b: u128) -> Option<u128> {
+        match self.modulus {
+            // a and b are already below the modulus, so this can't overflow
+            Some(modulus) if a >= modulus - b => Some(a - (modulus - b)),
+            Some(_) => Some(a + b),
+            None => a.checked_add(b),
+        }
+    }
+}
+
+pub fn day12_part1(inp: &str) -> usize {
+    let grid = inp
+        .lines()
+        .map(|line| line.chars().collect())
+        .collect::<Vec<Vec<_>>>();
+    let regions = label_regions(&grid);
+    regions
+        .regions
+        .iter()
+        .map(|region| region.len() * regions.perimeter(region))
+        .sum()
+}
+
+pub fn day12_part2(inp: &str) -> usize {
+    let grid = inp
+        .lines()
+        .map(|line| line.chars().collect())
+        .collect::<Vec<Vec<_>>>();
+    let regions = label_regions(&grid);
+    regions
+        .regions
+        .iter()
+        .map(|region| region.len() * regions.num_of_sides(region))
+        .sum()
+}
+
+// Connected areas of equal cells in a grid, where cells are connected to the 4 cells next to them.
+#[derive(Debug, Clone)]
+pub struct GridRegions {
+    // Index into `regions` for every cell.
+    pub labels: Vec<Vec<usize>>,
+    // The points in each region, in the order they were found.
+    pub regions: Vec<Vec<(usize, usize)>>,
+}
+
+pub fn label_regions<T: PartialEq>(grid: &[Vec<T>]) -> GridRegions {
+    let mut labels = grid
+        .iter()
+        .map(|row| vec![usize::MAX; row.len()])
+        .collect::<Vec<_>>();
+    let mut regions = Vec::new();
+    for (y, row) in grid.iter().enumerate() {
+        for x in 0..row.len() {
+            if labels[y][x] != usize::MAX {
+                continue;
+            }
+            // flood fill from this point
+            let label = regions.len();
+            let mut region = vec![(x, y)];
+            labels[y][x] = label;
+            let mut n = 0;
+            while n < region.len() {
+                let (x1, y1) = region[n];
+                for (x2, y2) in orthogonal_neighbours((x1, y1)) {
+                    let is_same = grid.get(y2).and_then(|row| row.get(x2)) == Some(&grid[y1][x1]);
+                    if is_same && labels[y2][x2] == usize::MAX {
+                        labels[y2][x2] = label;
+                        region.push((x2, y2));
+                    }
+                }
+                n += 1;
+            }
+            regions.push(region);
+        }
+    }
+    GridRegions { labels, regions }
+}
+
+impl GridRegions {
+    pub fn region_at(&self, (x, y): (usize, usize)) -> &[(usize, usize)] {
+        &self.regions[self.labels[y][x]]
+    }
+
+    // Number of cell edges between the region and anything else, including the edge of the grid.
+    pub fn perimeter(&self, region: &[(usize, usize)]) -> usize {
+        region
+            .iter()
+            .map(|&(x, y)| {
+                orthogonal_neighbours((x, y))
+                    .filter(|&point| !self.is_same_region((x, y), point))
+                    .count()
+            })
+            .sum()
+    }
+
+    // Number of straight fence sections around the region. A polygon has as many sides as
+    // corners, so count the corners at each cell instead.
+    pub fn num_of_sides(&self, region: &[(usize, usize)]) -> usize {
+        let mut corners = 0;
+        for &point @ (x, y) in region {
+            for (dx, dy) in [(-1, -1), (1, -1), (1, 1), (-1, 1)] {
+                let (x, y) = (x as i64, y as i64);
+                let horizontal = self.is_same_region_signed(point, (x + dx, y));
+                let vertical = self.is_same_region_signed(point, (x, y + dy));
+                let diagonal = self.is_same_region_signed(point, (x + dx, y + dy));
+                // outside corner, or inside corner
+                if (!horizontal && !vertical) || (horizontal && vertical && !diagonal) {
+                    corners += 1;
+                }
+            }
+        }
+        corners
+    }
+
+    fn is_same_region(&self, (x, y): (usize, usize), (x1, y1): (usize, usize)) -> bool {
+        self.labels.get(y1).and_then(|row| row.get(x1)) == Some(&self.labels[y][x])
+    }
+
+    fn is_same_region_signed(&self, point: (usize, usize), (x1, y1): (i64, i64)) -> bool {
+        x1 >= 0 && y1 >= 0 && self.is_same_region(point, (x1 as usize, y1 as usize))
+    }
+}
+
+// The 4 points next to this one, in any grid. Points off the top or left of the grid wrap round
+// to usize::MAX, so they're never in it.
+fn orthogonal_neighbours((x, y): (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+    [
+        (x.wrapping_add(1), y),
+        (x.wrapping_sub(1), y),
+        (x, y.wrapping_add(1)),
+        (x, y.wrapping_sub(1)),
+    ]
+    .into_iter()
+}
+
+pub fn day13_part1(inp: &str) -> i64 {
+    ClawMachine::parse_all(inp)
+        .unwrap()
+        .iter()
+        .filter_map(|machine| machine.cheapest_presses(Some(100)))
+        .map(|(a, b)| 3 * a + b)
+        .sum()
+}
+
+pub fn day13_part2(inp: &str) -> i64 {
+    ClawMachine::parse_all(inp)
+        .unwrap()
+        .iter()
+        .map(|machine| ClawMachine {
+            prize: (
+                machine.prize.0 + 10_000_000_000_000,
+                machine.prize.1 + 10_000_000_000_000,
+            ),
+            ..*machine
+        })
+        .filter_map(|machine| machine.cheapest_presses(None))
+        .map(|(a, b)| 3 * a + b)
+        .sum()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClawMachine {
+    // (x, y) movement for each press of the button.
+    pub button_a: (i64, i64),
+    pub button_b: (i64, i64),
+    pub prize: (i64, i64),
+}
+
+impl ClawMachine {
+    // Parses machines separated by blank lines. Errors say which line (counting from 1) was
+    // wrong.
+    pub fn parse_all(inp: &str) -> Result<Vec<ClawMachine>, String> {
+        let mut machines = Vec::new();
+        let mut lines = inp
+            .lines()
+            .enumerate()
+            .map(|(line_num, line)| (line_num + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        while let Some(first_line) = lines.next() {
+            let mut next_line = |expected: &str| {
+                lines
+                    .next()
+                    .ok_or_else(|| format!("unexpected end of input, expected {expected:?} line"))
+            };
+            let button_b_line = next_line("Button B")?;
+            let prize_line = next_line("Prize")?;
+            machines.push(ClawMachine {
+                button_a: day13_parse_line(first_line, "Button A: ", '+')?,
+                button_b: day13_parse_line(button_b_line, "Button B: ", '+')?,
+                prize: day13_parse_line(prize_line, "Prize: ", '=')?,
+            });
+        }
+        Ok(machines)
+    }
+
+    // Returns the (A presses, B presses) that win the prize for the fewest tokens, or None if it
+    // can't be won.
+    pub fn cheapest_presses(&self, max_presses: Option<i64>) -> Option<(i64, i64)> {
+        let (ax, ay) = (self.button_a.0 as i128, self.button_a.1 as i128);
+        let (bx, by) = (self.button_b.0 as i128, self.button_b.1 as i128);
+        let (px, py) = (self.prize.0 as i128, self.prize.1 as i128);
+        let max_presses = max_presses.map(|max| max as i128);
+        let det = ax * by - ay * bx;
+        let (a, b) = if det != 0 {
+            // Cramer's rule. There's exactly one solution, which has to be whole presses.
+            let a_numerator = px * by - py * bx;
+            let b_numerator = ax * py - ay * px;
+            if a_numerator % det != 0 || b_numerator % det != 0 {
+                return None;
+            }
+            (a_numerator / det, b_numerator / det)
+        } else if (ax, bx) != (0, 0) {
+            // The buttons move in the same direction, so solve along one axis and check the
+            // other one afterwards.
+            day13_cheapest_1d(ax, bx, px, max_presses)?
+        } else {
+            day13_cheapest_1d(ay, by, py, max_presses)?
+        };
+        let in_range = |presses: i128| presses >= 0 && max_presses.is_none_or(|max| presses <= max);
+        if !in_range(a) || !in_range(b) || a * ax + b * bx != px || a * ay + b * by != py {
+            return None;
+        }
+        Some((a.try_into().ok()?, b.try_into().ok()?))
+    }
+}
+
+fn day13_parse_line(
+    (line_num, line): (usize, &str),
+    prefix: &str,
+    separator: char,
+) -> Result<(i64, i64), String> {
+    let error = || {
+        format!(
+            "line {line_num}: expected \"{prefix}X{separator}.., Y{separator}..\", found {line:?}"
+        )
+    };
+    let (x, y) = line
+        .strip_prefix(prefix)
+        .and_then(|rest| rest.split_once(", "))
+        .ok_or_else(error)?;
+    let parse_coord = |coord: &str, axis: char| {
+        let num = coord
+            .strip_prefix(axis)
+            .and_then(|rest| rest.strip_prefix(separator))
+            .ok_or_else(error)?;
+        num.parse::<i64>()
+            .map_err(|e| format!("line {line_num}: invalid number {num:?}: {e}"))
+    };
+    Ok((parse_coord(x, 'X')?, parse_coord(y, 'Y')?))
+}
+
+// Cheapest whole, non-negative (a, b) with a * u + b * v == w, costing 3 per a and 1 per b.
+fn day13_cheapest_1d(u: i128, v: i128, w: i128, max_presses: Option<i128>) -> Option<(i128, i128)> {
+    if u == 0 && v == 0 {
+        // neither button moves, so the prize is only reachable if it's already under the claw
+        return (w == 0).then_some((0, 0));
+    }
+    if v == 0 {
+        return (w % u == 0).then_some((w / u, 0));
+    }
+    if u == 0 {
+        return (w % v == 0).then_some((0, w / v));
+    }
+    let (g, s, t) = day13_extended_gcd(u, v);
+    if w % g != 0 {
+        return None;
+    }
+    // Every solution is (a0 + k * da, b0 - k * db) for some k.
+    let (a0, b0) = (s * (w / g), t * (w / g));
+    let (da, db) = (v / g, u / g);
+    let mut min_k = i128::MIN;
+    let mut max_k = i128::MAX;
+    let max = max_presses.unwrap_or(i128::MAX / 4);
+    // 0 <= start + k * step <= max, for a and b
+    for (start, step) in [(a0, da), (b0, -db)] {
+        let (low, high) = if step > 0 {
+            (
+                day13_div_ceil(-start, step),
+                day13_div_floor(max - start, step),
+            )
+        } else {
+            (
+                day13_div_ceil(max - start, step),
+                day13_div_floor(-start, step),
+            )
+        };
+        min_k = min_k.max(low);
+        max_k = max_k.min(high);
+    }
+    if min_k > max_k {
+        return None;
+    }
+    // the cost changes by the same amount for every step of k, so the cheapest is at one end
+    let k = if 3 * da - db > 0 { min_k } else { max_k };
+    Some((a0 + k * da, b0 - k * db))
+}
+
+// Returns (g, s, t) where g = gcd(a, b) and s * a + t * b == g.
+fn day13_extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, s, t) = day13_extended_gcd(b, a % b);
+        (g, t, s - (a / b) * t)
+    }
+}
+
+fn day13_div_floor(a: i128, b: i128) -> i128 {
+    let quotient = a / b;
+    if a % b != 0 && ((a < 0) != (b < 0)) {
+        quotient - 1
+    } else {
+        quotient
+    }
+}
+
+fn day13_div_ceil(a: i128, b: i128) -> i128 {
+    let quotient = a / b;
+    if a % b != 0 && ((a < 0) == (b < 0)) {
+        quotient + 1
+    } else {
+        quotient
+    }
+}
+
+// The puzzle uses an 11x7 space for the example and 101x103 for the real input.
+pub fn day14_part1(inp: &str, width: i64, height: i64) -> usize {
+    let robots = day14_parse(inp);
+    let positions = day14_positions_after(&robots, width, height, 100);
+    let mut quadrant_counts = [0; 4];
+    for (x, y) in positions {
+        if x == width / 2 || y == height / 2 {
+            continue;
+        }
+        let quadrant = (x > width / 2) as usize + 2 * (y > height / 2) as usize;
+        quadrant_counts[quadrant] += 1;
+    }
+    quadrant_counts.into_iter().product()
+}
+
+pub fn day14_part2(inp: &str, width: i64, height: i64) -> usize {
+    day14_find_tree(inp, width, height, Day14TreeHeuristic::MinVariance).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Robot {
+    pub position: (i64, i64),
+    pub velocity: (i64, i64),
+}
+
+pub fn day14_parse(inp: &str) -> Vec<Robot> {
+    let parse_pair = |pair: &str| {
+        let (x, y) = pair.split_once(',').unwrap();
+        (x.parse().unwrap(), y.parse().unwrap())
+    };
+    inp.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (position, velocity) = line.trim().split_once(' ').unwrap();
+            Robot {
+                position: parse_pair(position.strip_prefix("p=").unwrap()),
+                velocity: parse_pair(velocity.strip_prefix("v=").unwrap()),
+            }
+        })
+        .collect()
+}
+
+// Robots wrap round the edges, so each one's position only depends on the number of seconds.
+pub fn day14_positions_after(
+    robots: &[Robot],
+    width: i64,
+    height: i64,
+    seconds: usize,
+) -> Vec<(i64, i64)> {
+    let seconds = seconds as i64;
+    robots
+        .iter()
+        .map(|robot| {
+            (
+                (robot.position.0 + robot.velocity.0 * seconds).rem_euclid(width),
+                (robot.position.1 + robot.velocity.1 * seconds).rem_euclid(height),
+            )
+        })
+        .collect()
+}
+
+// Draws the space as in the puzzle description: the number of robots on each tile, or '.' if
+// there are none.
+pub fn day14_render(inp: &str, width: i64, height: i64, seconds: usize) -> String {
+    let robots = day14_parse(inp);
+    let mut counts = HashMap::new();
+    for position in day14_positions_after(&robots, width, height, seconds) {
+        *counts.entry(position).or_insert(0) += 1;
+    }
+    (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| match counts.get(&(x, y)) {
+                    None => '.',
+                    Some(&count) => char::from_digit(count, 10).unwrap_or('+'),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Day14TreeHeuristic {
+    // The robots bunch together while drawing the tree. x positions repeat every `width`
+    // seconds and y positions every `height` seconds, so the spread along each axis only needs
+    // working out for that many seconds, and then the second where the two added together is
+    // smallest is found.
+    MinVariance,
+    // The tree is drawn on the first second when no two robots share a tile.
+    NoOverlap,
+}
+
+// Returns the first second at which the robots draw a Christmas tree, according to the
+// heuristic.
+pub fn day14_find_tree(
+    inp: &str,
+    width: i64,
+    height: i64,
+    heuristic: Day14TreeHeuristic,
+) -> Option<usize> {
+    let robots = day14_parse(inp);
+    let period = day14_lcm(width as usize, height as usize);
+    match heuristic {
+        Day14TreeHeuristic::MinVariance => {
+            let variance_at = |seconds: usize, axis: fn(&(i64, i64)) -> i64| {
+                let values = day14_positions_after(&robots, width, height, seconds)
+                    .iter()
+                    .map(axis)
+                    .collect::<Vec<_>>();
+                let mean = values.iter().sum::<i64>() as f64 / values.len() as f64;
+                values
+                    .iter()
+                    .map(|&value| (value as f64 - mean).powi(2))
+                    .sum::<f64>()
+            };
+            let x_variances = (0..width as usize)
+                .map(|seconds| variance_at(seconds, |position| position.0))
+                .collect::<Vec<_>>();
+            let y_variances = (0..height as usize)
+                .map(|seconds| variance_at(seconds, |position| position.1))
+                .collect::<Vec<_>>();
+            // when the width and height are coprime this is the same as finding the least spread
+            // out second for each axis and combining them with the Chinese remainder theorem,
+            // but it works for any grid size
+            let total_variance_at = |seconds: usize| {
+                x_variances[seconds % width as usize] + y_variances[seconds % height as usize]
+            };
+            (0..period).min_by(|&a, &b| total_variance_at(a).total_cmp(&total_variance_at(b)))
+        }
+        Day14TreeHeuristic::NoOverlap => (0..period).find(|&seconds| {
+            let positions = day14_positions_after(&robots, width, height, seconds);
+            positions.iter().collect::<HashSet<_>>().len() == positions.len()
+        }),
+    }
+}
+
+fn day14_lcm(a: usize, b: usize) -> usize {
+    let (mut x, mut y) = (a, b);
+    while y != 0 {
+        (x, y) = (y, x % y);
+    }
+    a / x * b
+}
+
+pub fn day15_part1(inp: &str) -> usize {
+    let (mut warehouse, moves) = day15_parse(inp, false);
+    for direction in moves {
+        warehouse.try_move(direction);
+    }
+    warehouse.gps_sum()
+}
+
+pub fn day15_part2(inp: &str) -> usize {
+    let (mut warehouse, moves) = day15_parse(inp, true);
+    for direction in moves {
+        warehouse.try_move(direction);
+    }
+    warehouse.gps_sum()
+}
+
+// Returns the warehouse and the robot's moves. If widened is set, everything except the robot
+// is made twice as wide, as in part 2.
+pub fn day15_parse(inp: &str, widened: bool) -> (Warehouse, Vec<char>) {
+    let inp = inp.replace("\r\n", "\n");
+    let (map, moves) = inp.split_once("\n\n").unwrap();
+    let map = if widened {
+        map.chars()
+            .map(|chr| match chr {
+                '#' => "##",
+                'O' => "[]",
+                '@' => "@.",
+                '\n' => "\n",
+                _ => "..",
+            })
+            .collect()
+    } else {
+        map.to_string()
+    };
+    let moves = moves.chars().filter(|chr| !chr.is_whitespace()).collect();
+    (Warehouse::new(&map), moves)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warehouse {
+    grid: Vec<Vec<char>>,
+    robot: (usize, usize),
+}
+
+impl Warehouse {
+    pub fn new(map: &str) -> Self {
+        let grid = map
+            .lines()
+            .map(|line| line.chars().collect())
+            .collect::<Vec<Vec<_>>>();
+        let robot = grid
+            .iter()
+            .enumerate()
+            .find_map(|(y, row)| row.iter().position(|&chr| chr == '@').map(|x| (x, y)))
+            .unwrap();
+        Warehouse { grid, robot }
+    }
+
+    pub fn robot(&self) -> (usize, usize) {
+        self.robot
+    }
+
+    // Moves the robot one step in the direction ('^', 'v', '<' or '>'), pushing any boxes in
+    // the way. Returns false, leaving everything where it was, if something hits a wall.
+    pub fn try_move(&mut self, direction: char) -> bool {
+        let (dx, dy): (i64, i64) = match direction {
+            '^' => (0, -1),
+            'v' => (0, 1),
+            '<' => (-1, 0),
+            '>' => (1, 0),
+            _ => panic!("unknown move {direction:?}"),
+        };
+        // find everything that would be pushed, starting with the robot
+        let mut to_move = vec![self.robot];
+        let mut n = 0;
+        while n < to_move.len() {
+            let (x, y) = to_move[n];
+            n += 1;
+            let next @ (next_x, next_y) = ((x as i64 + dx) as usize, (y as i64 + dy) as usize);
+            let mut pushed = vec![next];
+            match self.grid[next_y][next_x] {
+                '#' => return false,
+                'O' => {}
+                // wide boxes pushed up or down push both halves
+                '[' if dy != 0 => pushed.push((next_x + 1, next_y)),
+                ']' if dy != 0 => pushed.push((next_x - 1, next_y)),
+                '[' | ']' => {}
+                _ => continue,
+            }
+            for point in pushed {
+                if !to_move.contains(&point) {
+                    to_move.push(point);
+                }
+            }
+        }
+        let moved = to_move
+            .iter()
+            .map(|&(x, y)| ((x, y), self.grid[y][x]))
+            .collect::<Vec<_>>();
+        for &((x, y), _) in &moved {
+            self.grid[y][x] = '.';
+        }
+        for ((x, y), chr) in moved {
+            self.grid[(y as i64 + dy) as usize][(x as i64 + dx) as usize] = chr;
+        }
+        self.robot = (
+            (self.robot.0 as i64 + dx) as usize,
+            (self.robot.1 as i64 + dy) as usize,
+        );
+        true
+    }
+
+    // Sum of 100 * y + x for every box, measured from the box's left edge.
+    pub fn gps_sum(&self) -> usize {
+        self.grid
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, &chr)| chr == 'O' || chr == '[')
+                    .map(move |(x, _)| 100 * y + x)
+            })
+            .sum()
+    }
+
+    pub fn render(&self) -> String {
+        self.grid
+            .iter()
+            .map(|row| row.iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// The map after each move, for debugging. The first entry is the map before any moves.
+pub fn day15_maps_after_each_move(inp: &str, widened: bool) -> Vec<String> {
+    let (mut warehouse, moves) = day15_parse(inp, widened);
+    let mut maps = vec![warehouse.render()];
+    for direction in moves {
+        warehouse.try_move(direction);
+        maps.push(warehouse.render());
+    }
+    maps
+}
+
+pub fn day16_part1(inp: &str) -> u64 {
+    let maze = Day16Maze::new(inp);
+    let paths = maze.shortest_paths();
+    maze.end_states()
+        .iter()
+        .filter_map(|state| paths.costs.get(state))
+        .copied()
+        .min()
+        .unwrap()
+}
+
+pub fn day16_part2(inp: &str) -> usize {
+    let maze = Day16Maze::new(inp);
+    let paths = maze.shortest_paths();
+    let best_cost = maze
+        .end_states()
+        .iter()
+        .filter_map(|state| paths.costs.get(state))
+        .copied()
+        .min()
+        .unwrap();
+    let best_end_states = maze
+        .end_states()
+        .into_iter()
+        .filter(|state| paths.costs.get(state) == Some(&best_cost))
+        .collect::<Vec<_>>();
+    paths
+        .states_on_paths_to(&best_end_states)
+        .into_iter()
+        .map(|(position, _)| position)
+        .collect::<HashSet<_>>()
+        .len()
+}
+
+// A reindeer's (position, facing direction as an (x, y) step).
+type Day16State = ((usize, usize), (i64, i64));
+
+struct Day16Maze {
+    grid: Vec<Vec<char>>,
+    start: (usize, usize),
+    end: (usize, usize),
+}
+
+impl Day16Maze {
+    fn new(inp: &str) -> Self {
+        let grid = inp
+            .lines()
+            .map(|line| line.chars().collect())
+            .collect::<Vec<Vec<_>>>();
+        let find = |target| {
+            grid.iter()
+                .enumerate()
+                .find_map(|(y, row)| row.iter().position(|&chr| chr == target).map(|x| (x, y)))
+                .unwrap()
+        };
+        let (start, end) = (find('S'), find('E'));
+        Day16Maze { grid, start, end }
+    }
+
+    fn end_states(&self) -> Vec<Day16State> {
+        [(1, 0), (-1, 0), (0, 1), (0, -1)]
+            .into_iter()
+            .map(|direction| (self.end, direction))
+            .collect()
+    }
+
+    // Moving forward costs 1 and turning 90 degrees costs 1000. The reindeer starts facing east.
+    fn shortest_paths(&self) -> ShortestPaths<Day16State> {
+        shortest_paths([(self.start, (1, 0))], |&((x, y), (dx, dy))| {
+            let forward = ((x as i64 + dx) as usize, (y as i64 + dy) as usize);
+            let mut next_states = vec![(((x, y), (-dy, dx)), 1000), (((x, y), (dy, -dx)), 1000)];
+            if self.grid[forward.1][forward.0] != '#' {
+                next_states.push(((forward, (dx, dy)), 1));
+            }
+            next_states
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<S> {
+    // Lowest cost to reach every reachable state.
+    pub costs: HashMap<S, u64>,
+    // For every state, the states just before it on its lowest-cost paths.
+    pub predecessors: HashMap<S, Vec<S>>,
+}
+
+impl<S: Clone + Eq + Hash> ShortestPaths<S> {
+    // Every state on a lowest-cost path to any of the given states, including the states
+    // themselves.
+    pub fn states_on_paths_to(&self, ends: &[S]) -> HashSet<S> {
+        let mut states = ends.iter().cloned().collect::<HashSet<_>>();
+        let mut to_visit = ends.to_vec();
+        while let Some(state) = to_visit.pop() {
+            for predecessor in self.predecessors.get(&state).into_iter().flatten() {
+                if states.insert(predecessor.clone()) {
+                    to_visit.push(predecessor.clone());
+                }
+            }
+        }
+        states
+    }
+}
+
+// Dijkstra's algorithm, over any states. `neighbours` returns the states that can be reached in
+// one step from a state, with the cost of the step.
+pub fn shortest_paths<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> ShortestPaths<S>
+where
+    S: Clone + Eq + Hash + Ord,
+    I: IntoIterator<Item = (S, u64)>,
+{
+    let mut costs = HashMap::new();
+    let mut predecessors: HashMap<S, Vec<S>> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        costs.insert(start.clone(), 0);
+        queue.push(Reverse((0, start)));
+    }
+    while let Some(Reverse((cost, state))) = queue.pop() {
+        if costs.get(&state).is_some_and(|&best_cost| cost > best_cost) {
+            continue;
+        }
+        for (next_state, step_cost) in neighbours(&state) {
+            let next_cost = cost + step_cost;
+            match costs.get(&next_state) {
+                Some(&best_cost) if next_cost > best_cost => {}
+                Some(&best_cost) if next_cost == best_cost => {
+                    predecessors
+                        .entry(next_state)
+                        .or_default()
+                        .push(state.clone());
+                }
+                _ => {
+                    costs.insert(next_state.clone(), next_cost);
+                    predecessors.insert(next_state.clone(), vec![state.clone()]);
+                    queue.push(Reverse((next_cost, next_state)));
+                }
+            }
+        }
+    }
+    ShortestPaths {
+        costs,
+        predecessors,
+    }
+}
+
+// Breadth-first search, over any states. Returns the fewest steps from any of the starts to
+// every reachable state. Every step costs the same, so there's no need for `shortest_paths`.
+pub fn fewest_steps<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbours: impl FnMut(&S) -> I,
+) -> HashMap<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut steps = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !steps.contains_key(&start) {
+            steps.insert(start.clone(), 0);
+            queue.push_back(start);
+        }
+    }
+    while let Some(state) = queue.pop_front() {
+        let next_steps = steps[&state] + 1;
+        for next_state in neighbours(&state) {
+            if !steps.contains_key(&next_state) {
+                steps.insert(next_state.clone(), next_steps);
+                queue.push_back(next_state);
+            }
+        }
+    }
+    steps
+}
+
+pub fn day17_part1(inp: &str) -> String {
+    let mut computer = Day17Computer::new(inp);
+    computer
+        .run()
+        .unwrap()
+        .iter()
+        .map(|value| value.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn day17_part2(inp: &str) -> u64 {
+    Day17Computer::new(inp).find_quine_register_a().unwrap()
+}
+
+// Far more instructions than any of the puzzle's programs need, but few enough that a program
+// stuck in a loop is caught quickly.
+const DAY17_DEFAULT_MAX_STEPS: u64 = 10_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Day17Computer {
+    // A, B and C.
+    pub registers: [u64; 3],
+    pub program: Vec<u8>,
+}
+
+impl Day17Computer {
+    pub fn new(inp: &str) -> Self {
+        let mut registers = [0; 3];
+        let mut program = Vec::new();
+        for line in inp.lines() {
+            if let Some((name, value)) = line
+                .strip_prefix("Register ")
+                .and_then(|rest| rest.split_once(": "))
+            {
+                let index = match name {
+                    "A" => 0,
+                    "B" => 1,
+                    "C" => 2,
+                    _ => panic!("unknown register {name:?}"),
+                };
+                registers[index] = value.trim().parse().unwrap();
+            } else if let Some(values) = line.strip_prefix("Program: ") {
+                program = values
+                    .trim()
+                    .split(',')
+                    .map(|value| value.parse().unwrap())
+                    .collect();
+            }
+        }
+        Day17Computer { registers, program }
+    }
+
+    // Runs the program until it halts, returning everything it output. Fails if it runs for
+    // more than DAY17_DEFAULT_MAX_STEPS instructions, as it's probably stuck in a loop.
+    pub fn run(&mut self) -> Result<Vec<u8>, String> {
+        self.run_with_max_steps(DAY17_DEFAULT_MAX_STEPS)
+    }
+
+    pub fn run_with_max_steps(&mut self, max_steps: u64) -> Result<Vec<u8>, String> {
+        self.run_with_limits(usize::MAX, max_steps)
+    }
+
+    // Stops early once the output has more values than the limit.
+    fn run_with_limits(
+        &mut self,
+        max_output_len: usize,
+        max_steps: u64,
+    ) -> Result<Vec<u8>, String> {
+        let mut output = Vec::new();
+        let mut ip = 0;
+        let mut steps = 0;
+        while ip + 1 < self.program.len() && output.len() <= max_output_len {
+            if steps == max_steps {
+                return Err(format!(
+                    "program didn't halt within {max_steps} instructions"
+                ));
+            }
+            steps += 1;
+            let (opcode, operand) = (self.program[ip], self.program[ip + 1]);
+            let [a, b, c] = self.registers;
+            let combo = || match operand {
+                0..=3 => operand as u64,
+                4 => a,
+                5 => b,
+                6 => c,
+                _ => panic!("combo operand {operand} is reserved"),
+            };
+            let shift = |value: u64, amount: u64| {
+                value
+                    .checked_shr(amount.try_into().unwrap_or(u32::MAX))
+                    .unwrap_or(0)
+            };
+            ip += 2;
+            match opcode {
+                0 => self.registers[0] = shift(a, combo()),
+                1 => self.registers[1] = b ^ operand as u64,
+                2 => self.registers[1] = combo() % 8,
+                3 if a != 0 => ip = operand as usize,
+                3 => {}
+                4 => self.registers[1] = b ^ c,
+                5 => output.push((combo() % 8) as u8),
+                6 => self.registers[1] = shift(a, combo()),
+                7 => self.registers[2] = shift(a, combo()),
+                _ => panic!("unknown opcode {opcode}"),
+            }
+        }
+        Ok(output)
+    }
+
+    // One instruction per line, with its address, e.g. "0: bst A".
+    pub fn disassemble(&self) -> String {
+        self.program
+            .chunks(2)
+            .enumerate()
+            .map(|(n, instruction)| {
+                let opcode = instruction[0];
+                let operand = instruction.get(1).copied().unwrap_or(0);
+                let combo = match operand {
+                    0..=3 => operand.to_string(),
+                    4 => "A".to_string(),
+                    5 => "B".to_string(),
+                    6 => "C".to_string(),
+                    _ => "?".to_string(),
+                };
+                let (mnemonic, operand) = match opcode {
+                    0 => ("adv", combo),
+                    1 => ("bxl", operand.to_string()),
+                    2 => ("bst", combo),
+                    3 => ("jnz", operand.to_string()),
+                    4 => ("bxc", operand.to_string()),
+                    5 => ("out", combo),
+                    6 => ("bdv", combo),
+                    7 => ("cdv", combo),
+                    _ => ("???", operand.to_string()),
+                };
+                format!("{}: {mnemonic} {operand}", n * 2)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    // Finds the lowest value of register A that makes the program output itself.
+    //
+    // This relies on the program having the same shape as the puzzle inputs: a loop that outputs
+    // one value based on the low bits of A, then shifts A right by 3 bits. That means the last
+    // output only depends on the top 3 bits of A, the one before on the top 6 bits, and so on, so
+    // A can be built up 3 bits at a time, working backwards from the end of the program.
+    pub fn find_quine_register_a(&self) -> Option<u64> {
+        self.find_register_a_bits(0, self.program.len())
+    }
+
+    // `a` has the bits that produce the outputs from `outputs_matched` onwards.
+    fn find_register_a_bits(&self, a: u64, outputs_matched: usize) -> Option<u64> {
+        if outputs_matched == 0 {
+            return Some(a);
+        }
+        let expected_output = &self.program[(outputs_matched - 1)..];
+        (0..8).find_map(|bits| {
+            let candidate = a.checked_mul(8)? + bits;
+            let mut computer = Day17Computer {
+                registers: [candidate, self.registers[1], self.registers[2]],
+                program: self.program.clone(),
+            };
+            // The puzzle's programs make one pass through the program per output, so a few passes
+            // per output is plenty. A candidate that loops forever is just one that doesn't match.
+            let max_steps = (expected_output.len() + 1) as u64 * self.program.len() as u64 * 4;
+            let output = computer.run_with_limits(expected_output.len(), max_steps);
+            if output.is_ok_and(|output| output == expected_output) {
+                self.find_register_a_bits(candidate, outputs_matched - 1)
+            } else {
+                None
+            }
+        })
+    }
+}
+
+// The memory space runs from 0 to `size - 1` on each axis. The puzzle uses 7 for the example
+// and 71 for the real input.
+pub fn day18_part1(inp: &str, size: usize, num_of_fallen_bytes: usize) -> usize {
+    let bytes = day18_parse(inp);
+    day18_steps_to_exit(&bytes[..num_of_fallen_bytes], size).unwrap()
+}
+
+pub fn day18_part2(inp: &str, size: usize) -> String {
+    let bytes = day18_parse(inp);
+    let (x, y) = bytes[day18_first_blocking_byte(&bytes, size).unwrap()];
+    format!("{x},{y}")
+}
+
+pub fn day18_parse(inp: &str) -> Vec<(usize, usize)> {
+    inp.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (x, y) = line.trim().split_once(',').unwrap();
+            (x.parse().unwrap(), y.parse().unwrap())
+        })
+        .collect()
+}
+
+// Fewest steps from the top left to the bottom right, avoiding the corrupted bytes, or None if
+// the exit can't be reached.
+pub fn day18_steps_to_exit(corrupted: &[(usize, usize)], size: usize) -> Option<usize> {
+    let corrupted = corrupted.iter().collect::<HashSet<_>>();
+    if corrupted.contains(&(0, 0)) {
+        return None;
+    }
+    let steps = fewest_steps([(0, 0)], |&point: &(usize, usize)| {
+        orthogonal_neighbours(point)
+            .filter(|&(x, y)| x < size && y < size && !corrupted.contains(&(x, y)))
+            .collect::<Vec<_>>()
+    });
+    steps.get(&(size - 1, size - 1)).copied()
+}
+
+// Returns the index of the first byte that cuts the exit off, or None if it can still be
+// reached after every byte has fallen.
+//
+// Rather than searching again after every byte, this starts with every byte fallen and takes
+// them away again in reverse order, joining up the open cells with a union-find. The byte that
+// finally connects the start to the exit is the one that first blocked it.
+pub fn day18_first_blocking_byte(bytes: &[(usize, usize)], size: usize) -> Option<usize> {
+    let index = |(x, y): (usize, usize)| y * size + x;
+    let mut num_of_bytes_at = vec![0; size * size];
+    for &byte in bytes {
+        num_of_bytes_at[index(byte)] += 1;
+    }
+    let mut open_cells = DisjointSets::new(size * size);
+    let open_neighbours = |point: (usize, usize), num_of_bytes_at: &[usize]| {
+        orthogonal_neighbours(point)
+            .filter(|&(x, y)| x < size && y < size && num_of_bytes_at[index((x, y))] == 0)
+            .collect::<Vec<_>>()
+    };
+    for y in 0..size {
+        for x in 0..size {
+            if num_of_bytes_at[index((x, y))] == 0 {
+                for neighbour in open_neighbours((x, y), &num_of_bytes_at) {
+                    open_cells.union(index((x, y)), index(neighbour));
+                }
+            }
+        }
+    }
+    let (start, exit) = (index((0, 0)), index((size - 1, size - 1)));
+    let is_connected = |open_cells: &mut DisjointSets, num_of_bytes_at: &[usize]| {
+        num_of_bytes_at[start] == 0
+            && num_of_bytes_at[exit] == 0
+            && open_cells.find(start) == open_cells.find(exit)
+    };
+    if is_connected(&mut open_cells, &num_of_bytes_at) {
+        return None;
+    }
+    for (n, &byte) in bytes.iter().enumerate().rev() {
+        num_of_bytes_at[index(byte)] -= 1;
+        if num_of_bytes_at[index(byte)] > 0 {
+            continue;
+        }
+        for neighbour in open_neighbours(byte, &num_of_bytes_at) {
+            open_cells.union(index(byte), index(neighbour));
+        }
+        if is_connected(&mut open_cells, &num_of_bytes_at) {
+            return Some(n);
+        }
     }
-    disk.iter()
-        .enumerate()
-        .map(|(n, &file_id)| n * file_id as usize)
-        .sum()
+    None
 }
 
-pub fn day9_part2(inp: &str) -> usize {
-    let mut disk: Vec<i32> = Vec::new();
-    // vec of (start_pos_in_disk, length_of_file). Position in this vec is the file ID.
-    let mut files = Vec::new();
-    for (n, digit) in inp
-        .chars()
-        .filter(|c| c != &'\n')
-        .map(|c| c.to_digit(10).unwrap())
-        .enumerate()
-    {
-        if n % 2 == 0 {
-            files.push((disk.len(), digit));
-            disk.append(&mut vec![(n / 2) as i32; digit as usize]);
-        } else {
-            disk.append(&mut vec![-1; digit as usize]);
+// Union-find over the numbers 0..n, with path compression and union by size.
+#[derive(Debug, Clone)]
+pub struct DisjointSets {
+    parents: Vec<usize>,
+    sizes: Vec<usize>,
+}
+
+impl DisjointSets {
+    pub fn new(n: usize) -> Self {
+        DisjointSets {
+            parents: (0..n).collect(),
+            sizes: vec![1; n],
         }
     }
-    for (file_id, &(file_start_pos, file_length)) in files.iter().enumerate().skip(1).rev() {
-        for (n, window) in disk.windows(file_length as usize).enumerate() {
-            if n >= file_start_pos {
-                break;
-            }
-            if window.iter().all(|&f| f == -1) {
-                for item in disk.iter_mut().skip(n).take(file_length as usize) {
-                    *item = file_id as i32;
-                }
-                for item in disk
-                    .iter_mut()
-                    .skip(file_start_pos)
-                    .take(file_length as usize)
-                {
-                    *item = -1;
-                }
-                while disk[disk.len() - 1] == -1 {
-                    disk.pop();
-                }
-                break;
-            }
+
+    // Returns the representative of the set containing x.
+    pub fn find(&mut self, x: usize) -> usize {
+        let mut root = x;
+        while self.parents[root] != root {
+            root = self.parents[root];
+        }
+        let mut x = x;
+        while self.parents[x] != root {
+            (x, self.parents[x]) = (self.parents[x], root);
         }
+        root
+    }
+
+    // Joins the sets containing a and b. Returns false if they were already the same set.
+    pub fn union(&mut self, a: usize, b: usize) -> bool {
+        let (mut a, mut b) = (self.find(a), self.find(b));
+        if a == b {
+            return false;
+        }
+        if self.sizes[a] < self.sizes[b] {
+            (a, b) = (b, a);
+        }
+        self.parents[b] = a;
+        self.sizes[a] += self.sizes[b];
+        true
     }
-    disk.iter()
-        .enumerate()
-        .map(|(n, &file_id)| {
-            if file_id == -1 {
-                0
-            } else {
-                n * file_id as usize
-            }
-        })
-        .sum()
 }
 
-pub fn day10_part1(inp: &str) -> usize {
-    let grid = inp
-        .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
-        .collect::<Vec<Vec<_>>>();
-    // find locations of all zeros
-    let mut res = 0;
-    for (y, row) in grid.iter().enumerate() {
-        for (x, &digit) in row.iter().enumerate() {
-            // for each zero, compute the trailhead score
-            if digit == 0 {
-                res += day10_compute_trailhead_score((x, y), &grid);
+pub fn day19_part1(inp: &str) -> usize {
+    let (towels, designs) = day19_parse(inp);
+    let towels = WordTrie::new(towels);
+    designs
+        .iter()
+        .filter(|design| day19_count_with_trie(&towels, design).unwrap_or(u64::MAX) > 0)
+        .count()
+}
+
+pub fn day19_part2(inp: &str) -> u64 {
+    let (towels, designs) = day19_parse(inp);
+    let towels = WordTrie::new(towels);
+    designs
+        .iter()
+        .map(|design| day19_count_with_trie(&towels, design).expect("count overflowed u64"))
+        .try_fold(0_u64, |total, count| total.checked_add(count))
+        .expect("total overflowed u64")
+}
+
+// Returns the towel patterns and the designs.
+pub fn day19_parse(inp: &str) -> (Vec<&str>, Vec<&str>) {
+    let mut lines = inp.lines().map(str::trim).filter(|line| !line.is_empty());
+    let towels = lines.next().unwrap().split(", ").collect();
+    (towels, lines.collect())
+}
+
+// Number of different ways to make the design out of the towels, or None if it doesn't fit in
+// a u64.
+pub fn day19_count_arrangements(towels: &[&str], design: &str) -> Option<u64> {
+    day19_count_with_trie(&WordTrie::new(towels.iter().copied()), design)
+}
+
+// Dynamic programming over prefixes of the design, finding the towels that fit after each
+// prefix by walking a trie of them.
+fn day19_count_with_trie(towels: &WordTrie, design: &str) -> Option<u64> {
+    let design = design.chars().collect::<Vec<_>>();
+    // ways[n] is the number of ways to make the first n colours of the design
+    let mut ways = vec![Some(0_u64); design.len() + 1];
+    ways[0] = Some(1);
+    for start in 0..design.len() {
+        let ways_to_start = ways[start];
+        if ways_to_start == Some(0) {
+            continue;
+        }
+        // every towel that matches from here extends the arrangements found so far
+        let mut node = 0;
+        for (end, colour) in design.iter().enumerate().skip(start) {
+            let Some(&next_node) = towels.nodes[node].children.get(colour) else {
+                break;
+            };
+            node = next_node;
+            if towels.nodes[node].word.is_some() {
+                ways[end + 1] = ways[end + 1]
+                    .zip(ways_to_start)
+                    .and_then(|(a, b)| a.checked_add(b));
             }
         }
     }
-    res
+    ways[design.len()]
 }
 
-pub fn day10_part2(inp: &str) -> usize {
+// The puzzle counts cheats saving at least 100 picoseconds. The examples use smaller thresholds.
+pub fn day20_part1(inp: &str, min_saving: usize) -> usize {
+    day20_savings_histogram(inp, 2, min_saving).values().sum()
+}
+
+pub fn day20_part2(inp: &str, min_saving: usize) -> usize {
+    day20_savings_histogram(inp, 20, min_saving).values().sum()
+}
+
+// For every amount of time saved (of at least min_saving), the number of cheats that save it.
+// A cheat can pass through walls for up to max_cheat_len steps. The track doesn't have to be a
+// single path: a cheat's saving is measured against the fastest route without cheating.
+pub fn day20_savings_histogram(
+    inp: &str,
+    max_cheat_len: usize,
+    min_saving: usize,
+) -> BTreeMap<usize, usize> {
     let grid = inp
         .lines()
-        .map(|line| line.chars().map(|c| c.to_digit(10).unwrap()).collect())
+        .map(|line| line.chars().collect())
         .collect::<Vec<Vec<_>>>();
-    // find locations of all zeros
-    let mut res = 0;
-    for (y, row) in grid.iter().enumerate() {
-        for (x, &digit) in row.iter().enumerate() {
-            // for each zero, compute the trailhead score
-            if digit == 0 {
-                res += day10_compute_trailhead_rating((x, y), digit, &grid);
+    let from_start = day20_distances_from(&grid, 'S');
+    let to_end = day20_distances_from(&grid, 'E');
+    let mut histogram = BTreeMap::new();
+    let Some(fastest) = day20_find(&grid, 'E').and_then(|end| from_start.get(&end)) else {
+        return histogram;
+    };
+    let max_cheat_len = max_cheat_len as i64;
+    for (&(x, y), &distance) in &from_start {
+        // try every track position within max_cheat_len steps of this one
+        for dy in -max_cheat_len..=max_cheat_len {
+            let max_dx = max_cheat_len - dy.abs();
+            for dx in -max_dx..=max_dx {
+                let (x1, y1) = (x as i64 + dx, y as i64 + dy);
+                if x1 < 0 || y1 < 0 {
+                    continue;
+                }
+                let cheat_len = (dx.abs() + dy.abs()) as usize;
+                let Some(saving) = to_end
+                    .get(&(x1 as usize, y1 as usize))
+                    .and_then(|remaining| fastest.checked_sub(distance + cheat_len + remaining))
+                else {
+                    continue;
+                };
+                if saving > 0 && saving >= min_saving {
+                    *histogram.entry(saving).or_insert(0) += 1;
+                }
             }
         }
     }
-    res
+    histogram
 }
 
-fn day10_compute_trailhead_score(position: (usize, usize), grid: &[Vec<u32>]) -> usize {
-    day10_get_trailhead_9height_positions(position, 0, grid).len()
+fn day20_find(grid: &[Vec<char>], target: char) -> Option<(usize, usize)> {
+    grid.iter()
+        .enumerate()
+        .find_map(|(y, row)| row.iter().position(|&chr| chr == target).map(|x| (x, y)))
 }
 
-fn day10_get_trailhead_9height_positions(
-    position: (usize, usize),
-    val: u32,
-    grid: &[Vec<u32>],
-) -> HashSet<(usize, usize)> {
-    if val == 9 {
-        return HashSet::from([position]);
+// Steps between the given tile and every point on the track, without cheating. Moves are
+// reversible, so this is the same both ways.
+fn day20_distances_from(grid: &[Vec<char>], tile: char) -> HashMap<(usize, usize), usize> {
+    let Some(start) = day20_find(grid, tile) else {
+        return HashMap::new();
+    };
+    fewest_steps([start], |&point: &(usize, usize)| {
+        orthogonal_neighbours(point)
+            .filter(|&(x, y)| {
+                grid.get(y)
+                    .and_then(|row| row.get(x))
+                    .is_some_and(|&chr| chr != '#')
+            })
+            .collect::<Vec<_>>()
+    })
+}
+
+pub fn day21_part1(inp: &str) -> u64 {
+    day21_complexity(inp, 2)
+}
+
+pub fn day21_part2(inp: &str) -> u64 {
+    day21_complexity(inp, 25)
+}
+
+// Sum over the codes of (fewest button presses) * (numeric part of the code), with the given
+// number of robots using directional keypads between the human and the numeric keypad robot.
+pub fn day21_complexity(inp: &str, num_of_directional_robots: usize) -> u64 {
+    let mut solver = KeypadSolver::new();
+    inp.lines()
+        .map(str::trim)
+        .filter(|code| !code.is_empty())
+        .map(|code| {
+            let numeric_part = code.trim_end_matches('A').parse::<u64>().unwrap();
+            solver.fewest_presses(code, num_of_directional_robots) * numeric_part
+        })
+        .sum()
+}
+
+// One optimal sequence of buttons for the human to press. The sequence roughly doubles in
+// length for each robot, so this is only practical for a few robots.
+pub fn day21_shortest_sequence(code: &str, num_of_directional_robots: usize) -> String {
+    KeypadSolver::new().sequence(code, num_of_directional_robots + 1)
+}
+
+const DAY21_NUMERIC_KEYPAD: [&str; 4] = ["789", "456", "123", " 0A"];
+const DAY21_DIRECTIONAL_KEYPAD: [&str; 2] = [" ^A", "<v>"];
+
+// Works out the cost of pressing a key on a keypad, through any number of layers of robots.
+// Every robot's arm starts on (and, after each key pressed on the keypad below it, returns to)
+// its 'A' button, so the cost of moving between two keys only depends on the keys and how many
+// layers are left, and can be memoised.
+pub struct KeypadSolver {
+    cache: HashMap<(char, char, usize), u64>,
+}
+
+impl Default for KeypadSolver {
+    fn default() -> Self {
+        Self::new()
     }
-    let surrounding_points = day10_get_surrounding_points(position, grid);
-    let mut res = HashSet::new();
-    for point @ (x, y) in surrounding_points {
-        if grid[y][x] == val + 1 {
-            res.extend(day10_get_trailhead_9height_positions(point, val + 1, grid));
+}
+
+impl KeypadSolver {
+    pub fn new() -> Self {
+        KeypadSolver {
+            cache: HashMap::new(),
         }
     }
-    res
-}
 
-fn day10_compute_trailhead_rating(position: (usize, usize), val: u32, grid: &[Vec<u32>]) -> usize {
-    if val == 9 {
-        return 1;
+    pub fn fewest_presses(&mut self, code: &str, num_of_directional_robots: usize) -> u64 {
+        self.sequence_cost(code, num_of_directional_robots + 1)
     }
-    let surrounding_points = day10_get_surrounding_points(position, grid);
-    let mut res = 0;
-    for point @ (x, y) in surrounding_points {
-        if grid[y][x] == val + 1 {
-            res += day10_compute_trailhead_rating(point, val + 1, grid);
+
+    // Cost of typing the keys, starting from 'A', on a keypad with `depth` directional keypads
+    // between it and the human. Depth 0 is the human's own keypad.
+    fn sequence_cost(&mut self, keys: &str, depth: usize) -> u64 {
+        let mut from = 'A';
+        let mut cost = 0;
+        for to in keys.chars() {
+            cost += self.cost(from, to, depth);
+            from = to;
         }
+        cost
     }
-    res
-}
 
-fn day10_get_surrounding_points((x, y): (usize, usize), grid: &[Vec<u32>]) -> Vec<(usize, usize)> {
-    let (x, y) = (x as i32, y as i32);
-    let height = grid.len() as i32;
-    let width = grid[0].len() as i32;
-    vec![(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)]
-        .into_iter()
-        .filter(|(x1, y1)| *x1 >= 0 && *y1 >= 0 && *x1 < width && *y1 < height)
-        .map(|(x1, y1)| (x1 as usize, y1 as usize))
-        .collect()
-}
-
-pub fn day11_part1(inp: &str) -> usize {
-    let mut stones = inp
-        .split(' ')
-        .map(|s| s.parse().unwrap())
-        .collect::<Vec<u64>>();
-    for _ in 0..25 {
-        stones = stones
+    // Cost of moving from one key to another and pressing it. The digits only appear on the
+    // numeric keypad and the arrows only on the directional one, so the keys alone say which
+    // keypad is meant.
+    fn cost(&mut self, from: char, to: char, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        if let Some(&cost) = self.cache.get(&(from, to, depth)) {
+            return cost;
+        }
+        let cost = day21_candidate_moves(from, to)
             .into_iter()
-            .flat_map(|stone| {
-                if stone == 0 {
-                    vec![1]
-                } else if ((stone.ilog10() + 1) % 2) == 0 {
-                    let num_of_digits = stone.ilog10() + 1;
-                    let x = stone / 10_u64.pow(num_of_digits / 2);
-                    let y = stone - (x * 10_u64.pow(num_of_digits / 2));
-                    vec![x, y]
-                } else {
-                    vec![stone * 2024]
-                }
-            })
-            .collect();
+            .map(|moves| self.sequence_cost(&moves, depth - 1))
+            .min()
+            .unwrap();
+        self.cache.insert((from, to, depth), cost);
+        cost
+    }
+
+    fn sequence(&mut self, keys: &str, depth: usize) -> String {
+        if depth == 0 {
+            return keys.to_string();
+        }
+        let mut from = 'A';
+        let mut sequence = String::new();
+        for to in keys.chars() {
+            let best_moves = day21_candidate_moves(from, to)
+                .into_iter()
+                .min_by_key(|moves| self.sequence_cost(moves, depth - 1))
+                .unwrap();
+            sequence += &self.sequence(&best_moves, depth - 1);
+            from = to;
+        }
+        sequence
     }
-    stones.len()
 }
 
-pub fn day11_part2(inp: &str) -> u64 {
-    let mut stones = inp
-        .split(' ')
-        .map(|s| (s.parse().unwrap(), 1))
-        .collect::<HashMap<u64, u64>>();
-    for _ in 0..75 {
-        stones = stones
+// The ways worth trying to move from one key to another, followed by pressing 'A'. Zig-zagging
+// is never better than doing all the horizontal moves together and all the vertical moves
+// together, so there are at most 2 ways, minus any that pass over the gap.
+fn day21_candidate_moves(from: char, to: char) -> Vec<String> {
+    let keypad = if DAY21_DIRECTIONAL_KEYPAD
+        .iter()
+        .any(|row| row.contains(from))
+        && DAY21_DIRECTIONAL_KEYPAD.iter().any(|row| row.contains(to))
+    {
+        &DAY21_DIRECTIONAL_KEYPAD[..]
+    } else {
+        &DAY21_NUMERIC_KEYPAD[..]
+    };
+    let position_of = |key: char| {
+        keypad
             .iter()
-            .map(|(stone, &count)| {
-                if *stone == 0 {
-                    HashMap::from([(1, count)])
-                } else if ((stone.ilog10() + 1) % 2) == 0 {
-                    let num_of_digits = stone.ilog10() + 1;
-                    let x = stone / 10_u64.pow(num_of_digits / 2);
-                    let y = stone - (x * 10_u64.pow(num_of_digits / 2));
-                    if x == y {
-                        HashMap::from([(x, count * 2)])
-                    } else {
-                        HashMap::from([(x, count), (y, count)])
-                    }
-                } else {
-                    HashMap::from([(stone * 2024, count)])
-                }
-            })
-            .reduce(|mut acc, map| {
-                for (stone, count) in map {
-                    acc.entry(stone)
-                        .and_modify(|count1| {
-                            *count1 += count;
-                        })
-                        .or_insert(count);
-                }
-                acc
-            })
-            .unwrap();
+            .enumerate()
+            .find_map(|(y, row)| row.find(key).map(|x| (x as i64, y as i64)))
+            .unwrap()
+    };
+    let (from_x, from_y) = position_of(from);
+    let (to_x, to_y) = position_of(to);
+    let gap = position_of(' ');
+    let horizontal = if to_x > from_x { ">" } else { "<" }.repeat(from_x.abs_diff(to_x) as usize);
+    let vertical = if to_y > from_y { "v" } else { "^" }.repeat(from_y.abs_diff(to_y) as usize);
+    let mut candidates = Vec::new();
+    if (to_x, from_y) != gap {
+        candidates.push(format!("{horizontal}{vertical}A"));
     }
-    stones.into_values().sum()
+    if (from_x, to_y) != gap && !candidates.contains(&format!("{vertical}{horizontal}A")) {
+        candidates.push(format!("{vertical}{horizontal}A"));
+    }
+    candidates
 }
 
 #[cfg(test)]
@@ -995,8 +3438,50 @@ mod tests {
         assert_eq!(day1_part1(test_input), 11);
         assert_eq!(day1_part1(&input), 1530215);
 
-        assert_eq!(day1_part2(&test_input), 31);
+        assert_eq!(day1_part2(test_input), 31);
         assert_eq!(day1_part2(&input), 26800609);
+
+        let lists = LocationLists::new(test_input);
+        assert_eq!(lists.left(), &[1, 2, 3, 3, 3, 4]);
+        assert_eq!(
+            lists.matched_pairs(),
+            vec![(1, 3), (2, 3), (3, 3), (3, 4), (3, 5), (4, 9)]
+        );
+        assert_eq!(lists.medians(), Some((3.0, 3.5)));
+        assert_eq!(lists.only_in_left(), BTreeSet::from([1, 2]));
+        assert_eq!(lists.only_in_right(), BTreeSet::from([5, 9]));
+        assert_eq!(lists.counts()[&3], (3, 3));
+        assert_eq!(lists.counts()[&9], (0, 1));
+        let messy_input = "3\t4\r\n4 3\r\n\r\n2     5  \n  1\t 3\n3   9\n3   3\n\n";
+        assert_eq!(day1_part1(messy_input), 11);
+        assert_eq!(day1_part2(messy_input), 31);
+        assert_eq!(
+            LocationLists::parse("3   4\n4   3   5\n").unwrap_err(),
+            "line 2: expected 2 columns, found 3"
+        );
+        assert_eq!(
+            LocationLists::parse("3   4\n\n4\n").unwrap_err(),
+            "line 3: expected 2 columns, found 1"
+        );
+        assert!(LocationLists::parse("3   x4")
+            .unwrap_err()
+            .starts_with("line 1: invalid location ID \"x4\""));
+        let file = fs::File::open("inputs/day1.txt").unwrap();
+        let lists = LocationLists::from_reader(std::io::BufReader::new(file)).unwrap();
+        assert_eq!(lists.total_distance(), 1530215);
+        assert_eq!(lists.similarity_score(), 26800609);
+        assert_eq!(
+            LocationLists::from_reader(&b"3   4\n\xff   3\n"[..]).unwrap_err(),
+            "line 2: stream did not contain valid UTF-8"
+        );
+        let generated_input = (0..100_000_u64)
+            .map(|n| format!("{}   {}\n", n, n % 1000))
+            .collect::<String>();
+        let lists = LocationLists::from_reader(generated_input.as_bytes()).unwrap();
+        assert_eq!(lists.similarity_score(), (0..1000).sum::<u64>() * 100);
+        let lists = LocationLists::new(&input);
+        assert_eq!(lists.total_distance(), 1530215);
+        assert_eq!(lists.similarity_score(), 26800609);
     }
 
     #[test]
@@ -1012,7 +3497,7 @@ mod tests {
         assert_eq!(day2_part1(test_input), 2);
         assert_eq!(day2_part1(&input), 269);
 
-        assert_eq!(day2_part2(&test_input), 4);
+        assert_eq!(day2_part2(test_input), 4);
         assert_eq!(day2_part2(&input), 337);
     }
 
@@ -1026,8 +3511,119 @@ mod tests {
         assert_eq!(day3_part1(test_input), 161);
         assert_eq!(day3_part1(&input), 174336360);
 
-        assert_eq!(day3_part2(&test_input_2), 48);
+        assert_eq!(day3_part2(test_input_2), 48);
         assert_eq!(day3_part2(&input), 88802350);
+
+        let file = fs::File::open("inputs/day3.txt").unwrap();
+        assert_eq!(
+            day3_scan_reader(file, Some(3)).unwrap(),
+            Day3Totals {
+                all_muls: Some(174336360),
+                enabled_muls: Some(88802350)
+            }
+        );
+        let corrupted = b"\xffmul(2,3)\x00\xfe\x80don't()mul(4,5)\xc3do()mul(6,7)";
+        assert_eq!(
+            day3_scan(corrupted, None),
+            Day3Totals {
+                all_muls: Some(68),
+                enabled_muls: Some(48)
+            }
+        );
+        // feed the input one byte at a time, so every instruction spans a chunk boundary
+        let mut scanner = Day3Scanner::new().with_recorded_instructions();
+        for byte in test_input_2.bytes() {
+            scanner.feed(&[byte]);
+        }
+        assert_eq!(scanner.totals().enabled_muls, Some(48));
+        assert_eq!(
+            scanner.take_instructions(),
+            vec![
+                Day3Instruction::Mul(2, 4),
+                Day3Instruction::Dont,
+                Day3Instruction::Mul(5, 5),
+                Day3Instruction::Mul(11, 8),
+                Day3Instruction::Do,
+                Day3Instruction::Mul(8, 5),
+            ]
+        );
+        assert!(scanner.take_instructions().is_empty());
+        let mut scanner = Day3Scanner::new();
+        scanner.feed(test_input_2.as_bytes());
+        assert!(scanner.take_instructions().is_empty());
+        assert_eq!(
+            day3_scan(b"mumul(1,mul(2,3)dodo()mul(,1)mul(4,)", None).all_muls,
+            Some(6)
+        );
+
+        assert_eq!(day3_part1("mul(2,3)mul(1000,1)mul(012,10)"), 126);
+        assert_eq!(day3_scan(b"mul(2,3)mul(1000,1)", None).all_muls, Some(1006));
+        assert_eq!(
+            day3_scan_reader(&b"mul(2,3)mul(1000,1)"[..], Some(4))
+                .unwrap()
+                .all_muls,
+            Some(1006)
+        );
+
+        let mut scanner = Day3Scanner::new()
+            .with_max_operand_digits(Some(3))
+            .with_recorded_rejections();
+        scanner.feed(b"mul(123,456)mul(1234,5)xmul(0001,2)mul(99999999999999999999999,1)");
+        scanner.feed(b"mul(4294967296,4294967296)");
+        assert_eq!(scanner.totals().all_muls, Some(123 * 456));
+        let too_many_digits = Day3RejectReason::TooManyDigits { max_digits: 3 };
+        assert_eq!(
+            scanner.rejections(),
+            &[
+                Day3Rejection {
+                    offset: 12,
+                    reason: too_many_digits
+                },
+                Day3Rejection {
+                    offset: 24,
+                    reason: too_many_digits
+                },
+                Day3Rejection {
+                    offset: 35,
+                    reason: too_many_digits
+                },
+                Day3Rejection {
+                    offset: 65,
+                    reason: too_many_digits
+                },
+            ]
+        );
+        let mut scanner = Day3Scanner::new().with_recorded_rejections();
+        scanner.feed(b"mul(99999999999999999999999,1)mul(4294967296,4294967296)mul(0001,2)");
+        assert_eq!(scanner.totals().all_muls, Some(2));
+        assert_eq!(
+            scanner
+                .rejections()
+                .iter()
+                .map(|rejection| rejection.reason)
+                .collect::<Vec<_>>(),
+            vec![Day3RejectReason::Overflow, Day3RejectReason::Overflow]
+        );
+        let mut scanner = Day3Scanner::new();
+        scanner.feed(b"mul(99999999999999999999999,1)");
+        assert!(scanner.rejections().is_empty());
+
+        // the products fit, but the sum of all of them doesn't
+        let mut scanner = Day3Scanner::new()
+            .with_recorded_instructions()
+            .with_recorded_rejections();
+        scanner.feed(b"mul(4294967295,4294967297)don't()mul(1,1)mul(2,3)do()");
+        assert_eq!(
+            scanner.totals(),
+            Day3Totals {
+                all_muls: None,
+                enabled_muls: Some(u64::MAX)
+            }
+        );
+        assert!(scanner.rejections().is_empty());
+        assert_eq!(scanner.take_instructions().len(), 5);
+        scanner.feed(b"mul(1,1)");
+        assert_eq!(scanner.totals().enabled_muls, None);
     }
 
     #[test]
@@ -1043,7 +3639,98 @@ SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX";
         assert_eq!(day4_part1(test_input), 18);
-        assert_eq!(day4_part2(&test_input), 9);
+        assert_eq!(day4_part2(test_input), 9);
+
+        let word_search = WordSearch::new(test_input);
+        let matches = word_search.find("XMAS");
+        assert_eq!(
+            matches[0],
+            WordMatch {
+                start: (4, 0),
+                direction: Day4Direction::DownRight
+            }
+        );
+        assert_eq!(matches[0].position_of_letter(3), (7, 3));
+        assert_eq!(word_search.find("SAMX").len(), 18);
+        assert_eq!(
+            word_search
+                .find_in_directions("XMAS", &[Day4Direction::Right, Day4Direction::Left])
+                .len(),
+            5
+        );
+        assert_eq!(word_search.find("MMMSXXMASM").len(), 1);
+        assert_eq!(word_search.find("XMASXMASXMAS").len(), 0);
+        assert_eq!(word_search.find("").len(), 0);
+        let num_of_xs = test_input.chars().filter(|&c| c == 'X').count();
+        assert_eq!(word_search.find("X").len(), num_of_xs);
+        assert_eq!(word_search.find_words(["X", "XM"]).count("X"), num_of_xs);
+
+        let dictionary = word_search.find_words(["XMAS", "MAS", "AM", "XMAS", "MASX", "QQ"]);
+        assert_eq!(dictionary.count("XMAS"), 18);
+        for word in ["MAS", "AM", "MASX", "QQ"] {
+            assert_eq!(dictionary.count(word), word_search.find(word).len());
+        }
+        assert_eq!(
+            dictionary.matches.len(),
+            ["XMAS", "MAS", "AM", "MASX"]
+                .iter()
+                .map(|word| word_search.find(word).len())
+                .sum::<usize>()
+        );
+        let only_xmas = word_search.find_words(["XMAS"]);
+        assert_eq!(only_xmas.cell_usage.values().sum::<usize>(), 18 * 4);
+        assert!(only_xmas.overlapping_cells() > 0);
+        assert!(only_xmas.overlapping_cells() < only_xmas.cell_usage.len());
+
+        assert_eq!(
+            day4_part1_highlighted(test_input, HighlightStyle::Text),
+            "....XXMAS.
+.SAMXMS...
+...S..A...
+..A.A.MS.X
+XMASAMX.MM
+X.....XA.A
+S.S.S.S.SS
+.A.A.A.A.A
+..M.M.M.MM
+.X.X.XMASX"
+        );
+        assert_eq!(
+            day4_part2_highlighted(test_input, HighlightStyle::Text),
+            ".M.S......
+..A..MSMS.
+.M.S.MAA..
+..A.ASMSM.
+.M.S.M....
+..........
+S.S.S.S.S.
+.A.A.A.A..
+M.M.M.M.M.
+.........."
+        );
+        let ansi = day4_part2_highlighted("MXS\nXAX\nMXS", HighlightStyle::Ansi);
+        assert!(ansi.starts_with("\x1b[1;32mM\x1b[0m\x1b[2mX\x1b[0m"));
+        assert_eq!(ansi.lines().count(), 3);
+
+        let x_mas = GridPattern::new("M.S\n.A.\nM.S", '.');
+        assert_eq!(x_mas.orientations().len(), 4);
+        let grid = Grid::new(test_input);
+        let x_mas_matches = grid.find_pattern(&x_mas, true);
+        assert_eq!(x_mas_matches[0].top_left, (1, 0));
+        assert_eq!(
+            x_mas_matches[0].cells().collect::<Vec<_>>(),
+            vec![(1, 0), (3, 0), (2, 1), (1, 2), (3, 2)]
+        );
+        assert_eq!(grid.find_pattern(&x_mas, false).len(), 2);
+        let l_shape = GridPattern::new("XM\n?A\n?S", '?');
+        assert_eq!(l_shape.orientations().len(), 8);
+        assert_eq!(l_shape.rotated().rotated().rotated().rotated(), l_shape);
+        assert_eq!(l_shape.rotated(), GridPattern::new("??X\nSAM", '?'));
+        assert_eq!(
+            grid.find_pattern(&GridPattern::new("..\n..", '.'), false)
+                .len(),
+            81
+        );
     }
 
     #[test]
@@ -1151,6 +3838,124 @@ MXMXAXMASX";
 10456732";
         assert_eq!(day10_part1(test_input), 36);
         assert_eq!(day10_part2(test_input), 81);
+
+        let summaries = day10_trailhead_summaries(test_input, &TrailRules::default()).unwrap();
+        assert_eq!(
+            summaries.iter().map(|s| s.1).collect::<Vec<_>>(),
+            vec![5, 6, 5, 3, 1, 3, 5, 3, 5]
+        );
+        assert_eq!(
+            summaries.iter().map(|s| s.2).collect::<Vec<_>>(),
+            vec![20, 24, 10, 4, 1, 4, 5, 8, 5]
+        );
+        assert_eq!(
+            day10_trails_from(test_input, (2, 0), &TrailRules::default())
+                .unwrap()
+                .len(),
+            20
+        );
+        assert!(
+            day10_trails_from(test_input, (0, 0), &TrailRules::default())
+                .unwrap()
+                .is_empty()
+        );
+
+        let impassable_input = "..90..9
+...1.98
+...2..7
+6543456
+765.987
+876....
+987....";
+        assert_eq!(day10_part1(impassable_input), 4);
+        let impassable_input_2 = ".....0.
+..4321.
+..5..2.
+..6543.
+..7..4.
+..8765.
+..9....";
+        assert_eq!(day10_part2(impassable_input_2), 3);
+
+        let diagonal_rules = TrailRules {
+            diagonal_moves: true,
+            ..TrailRules::default()
+        };
+        assert_eq!(
+            day10_total_rating("012\n.33\n..4", &diagonal_rules).unwrap(),
+            0
+        );
+        let short_rules = TrailRules {
+            end_height: 4,
+            ..diagonal_rules.clone()
+        };
+        assert_eq!(
+            day10_total_rating("012\n.33\n..4", &short_rules).unwrap(),
+            2
+        );
+        assert_eq!(day10_total_score("012\n.33\n..4", &short_rules).unwrap(), 1);
+        let big_step_rules = TrailRules {
+            start_height: 1,
+            end_height: 5,
+            climb_deltas: vec![1, 2],
+            diagonal_moves: true,
+            ..TrailRules::default()
+        };
+        assert_eq!(
+            day10_total_rating("123\n234\n345", &big_step_rules).unwrap(),
+            13
+        );
+        assert_eq!(
+            day10_trails_from("123\n234\n345", (0, 0), &big_step_rules)
+                .unwrap()
+                .len(),
+            13
+        );
+        let flat_rules = TrailRules {
+            climb_deltas: vec![0, 1],
+            ..TrailRules::default()
+        };
+        assert_eq!(
+            day10_trails_from("00", (0, 0), &flat_rules),
+            Err("climb deltas must all be at least 1".to_string())
+        );
+        assert!(day10_total_score("0123456789", &flat_rules).is_err());
+        assert_eq!(
+            day10_total_rating("0123\n45#6", &TrailRules::default()),
+            Err("line 2: invalid height '#'".to_string())
+        );
+
+        let small_input = "0123
+1234
+8765
+9876";
+        let trails = day10_trails_from(small_input, (0, 0), &TrailRules::default()).unwrap();
+        assert_eq!(trails.len(), 16);
+        assert!(trails.iter().all(|trail| trail.len() == 10));
+        assert_eq!(
+            day10_render_trail(small_input, &trails[0]),
+            "0123
+...4
+8765
+9..."
+        );
+
+        assert_eq!(day10_part1(""), 0);
+        assert_eq!(day10_part2(""), 0);
+        assert!(day10_trailhead_summaries("", &TrailRules::default())
+            .unwrap()
+            .is_empty());
+
+        let large_input = (0..1000)
+            .map(|y| {
+                (0..1000)
+                    .map(|x| char::from_digit((x + y) % 10, 10).unwrap())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_eq!(day10_part1(&large_input), 991000);
+        assert_eq!(day10_part2(&large_input), 50739200);
     }
 
     #[test]
@@ -1158,5 +3963,690 @@ MXMXAXMASX";
         let test_input = "125 17";
         assert_eq!(day11_part1(test_input), 55312);
         assert_eq!(day11_part2(test_input), 65601038650482);
+
+        let mut evolver = StoneEvolver::new(test_input);
+        assert_eq!(
+            evolver.counts_per_blink(6),
+            [2, 3, 4, 5, 9, 13, 22].map(Some)
+        );
+        assert_eq!(evolver.count_after(75), Some(65601038650482));
+        // too many stones to count without a modulus, but still quick to find out
+        assert_eq!(evolver.count_after(500), None);
+        let counts = evolver.counts_per_blink(500);
+        assert_eq!(counts[75], Some(65601038650482));
+        assert_eq!(counts[500], None);
+
+        let modulus = 1_000_000_007;
+        let mut evolver = StoneEvolver::new(test_input).with_modulus(modulus);
+        assert_eq!(evolver.count_after(75), Some(65601038650482 % modulus));
+        assert!(evolver.count_after(500).unwrap() < modulus);
+
+        let populations = StoneEvolver::new(test_input).populations(6);
+        assert_eq!(
+            populations
+                .iter()
+                .map(|p| p.distinct_values)
+                .collect::<Vec<_>>(),
+            vec![2, 3, 4, 5, 8, 12, 15]
+        );
+        assert_eq!(populations[6].largest_value, 2097446912);
+        assert_eq!(populations[6].histogram[&2], 4);
+        assert_eq!(populations[6].histogram.values().sum::<u128>(), 22);
+        let (blink, orbit) = StoneEvolver::new(test_input).closed_orbit(200).unwrap();
+        assert_eq!((blink, orbit.len()), (15, 76));
+        assert!(orbit.contains(&2097446912));
+        assert!(StoneEvolver::new(test_input).closed_orbit(5).is_none());
+
+        let three_way_split = StoneRule::Split { base: 10, parts: 3 };
+        assert_eq!(three_way_split.apply(123456), Some(vec![12, 34, 56]));
+        assert_eq!(three_way_split.apply(1234), None);
+        let binary_split = StoneRule::Split { base: 2, parts: 2 };
+        assert_eq!(binary_split.apply(0b1011), Some(vec![0b10, 0b11]));
+        assert_eq!(
+            StoneRule::Split { base: 10, parts: 1 }.apply(u64::MAX),
+            Some(vec![u64::MAX])
+        );
+        assert!(StoneRule::Split { base: 0, parts: 1 }.validate().is_err());
+        assert!(StoneRule::Split { base: 1, parts: 2 }.validate().is_err());
+        assert!(StoneRule::Split { base: 10, parts: 0 }.validate().is_err());
+        assert!(StoneRule::puzzle_rules()
+            .iter()
+            .all(|rule| rule.validate().is_ok()));
+        assert_eq!(
+            StoneEvolver::new(test_input)
+                .with_rules(vec![StoneRule::Split { base: 1, parts: 2 }])
+                .err(),
+            Some("split base must be at least 2, got 1".to_string())
+        );
+        let custom_rules = vec![
+            StoneRule::Custom(|stone| (stone % 7 == 0).then(|| vec![stone / 7, 1])),
+            three_way_split,
+            StoneRule::Multiply(3),
+        ];
+        let mut evolver = StoneEvolver::new(test_input)
+            .with_rules(custom_rules)
+            .unwrap();
+        let mut stones = vec![125, 17];
+        for count in evolver.counts_per_blink(12) {
+            assert_eq!(count, Some(stones.len() as u128));
+            stones = stones
+                .into_iter()
+                .flat_map(|stone| evolver.blink_stone(stone))
+                .collect();
+        }
+    }
+
+    #[test]
+    fn day12() {
+        let test_input = "AAAA
+BBCD
+BBCC
+EEEC";
+        let test_input_2 = "OOOOO
+OXOXO
+OOOOO
+OXOXO
+OOOOO";
+        let test_input_3 = "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE";
+        let test_input_4 = "EEEEE
+EXXXX
+EEEEE
+EXXXX
+EEEEE";
+        let test_input_5 = "AAAAAA
+AAABBA
+AAABBA
+ABBAAA
+ABBAAA
+AAAAAA";
+        assert_eq!(day12_part1(test_input), 140);
+        assert_eq!(day12_part1(test_input_2), 772);
+        assert_eq!(day12_part1(test_input_3), 1930);
+
+        assert_eq!(day12_part2(test_input), 80);
+        assert_eq!(day12_part2(test_input_2), 436);
+        assert_eq!(day12_part2(test_input_3), 1206);
+        assert_eq!(day12_part2(test_input_4), 236);
+        assert_eq!(day12_part2(test_input_5), 368);
+
+        let grid = test_input_2
+            .lines()
+            .map(|line| line.chars().collect())
+            .collect::<Vec<Vec<_>>>();
+        let regions = label_regions(&grid);
+        assert_eq!(regions.regions.len(), 5);
+        assert_eq!(regions.region_at((0, 0)).len(), 21);
+        assert_ne!(regions.labels[1][1], regions.labels[3][3]);
+    }
+
+    #[test]
+    fn day13() {
+        let test_input = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176
+
+Button A: X+17, Y+86
+Button B: X+84, Y+37
+Prize: X=7870, Y=6450
+
+Button A: X+69, Y+23
+Button B: X+27, Y+71
+Prize: X=18641, Y=10279
+";
+        assert_eq!(day13_part1(test_input), 480);
+        assert_eq!(day13_part2(test_input), 875318608908);
+
+        let machines = ClawMachine::parse_all(test_input).unwrap();
+        assert_eq!(machines[0].cheapest_presses(Some(100)), Some((80, 40)));
+        assert_eq!(machines[1].cheapest_presses(None), None);
+
+        // parallel buttons, where pressing B is cheaper per step
+        let parallel = ClawMachine {
+            button_a: (2, 4),
+            button_b: (1, 2),
+            prize: (10, 20),
+        };
+        assert_eq!(parallel.cheapest_presses(None), Some((0, 10)));
+        assert_eq!(parallel.cheapest_presses(Some(5)), Some((3, 4)));
+        assert_eq!(parallel.cheapest_presses(Some(2)), None);
+        // parallel buttons, where pressing A is cheaper per step
+        let parallel = ClawMachine {
+            button_a: (4, 4),
+            button_b: (1, 1),
+            prize: (10, 10),
+        };
+        assert_eq!(parallel.cheapest_presses(None), Some((2, 2)));
+        let off_line = ClawMachine {
+            prize: (10, 11),
+            ..parallel
+        };
+        assert_eq!(off_line.cheapest_presses(None), None);
+        assert_eq!(
+            day13_part1("Button A: X+0, Y+0\nButton B: X+0, Y+0\nPrize: X=0, Y=0"),
+            0
+        );
+        assert_eq!(
+            day13_part1("Button A: X+0, Y+0\nButton B: X+0, Y+0\nPrize: X=0, Y=3"),
+            0
+        );
+        let stuck = ClawMachine {
+            button_a: (0, 0),
+            button_b: (0, 0),
+            prize: (0, 0),
+        };
+        assert_eq!(stuck.cheapest_presses(Some(100)), Some((0, 0)));
+
+        assert_eq!(
+            ClawMachine::parse_all("Button A: X+94, Y+34\nButton B: X+22, Y+67\n").unwrap_err(),
+            "unexpected end of input, expected \"Prize\" line"
+        );
+        assert_eq!(
+            ClawMachine::parse_all("Button A: X+94, Y+34\nButton B: X+22 Y+67\nPrize: X=1, Y=2")
+                .unwrap_err(),
+            "line 2: expected \"Button B: X+.., Y+..\", found \"Button B: X+22 Y+67\""
+        );
+        assert_eq!(
+            ClawMachine::parse_all("Button A: X+94, Y+34\nButton B: X+22, Y+67\nPrize: X=1, Y=z")
+                .unwrap_err(),
+            "line 3: invalid number \"z\": invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn day14() {
+        let test_input = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+        assert_eq!(day14_part1(test_input, 11, 7), 12);
+        assert_eq!(
+            day14_render(test_input, 11, 7, 100),
+            "......2..1.
+...........
+1..........
+.11........
+.....1.....
+...12......
+.1....1...."
+        );
+        assert_eq!(day14_render("p=2,4 v=2,-3", 11, 7, 5), {
+            let mut rows = ["..........."; 7];
+            rows[3] = ".1.........";
+            rows.join("\n")
+        });
+
+        // Robots that draw a triangle after the given number of seconds, among robots scattered
+        // at random.
+        let make_tree_input = |width: i64, height: i64, tree_second: i64| {
+            let mut seed = 12345_u64;
+            let mut random = |limit: i64| {
+                seed = seed
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((seed >> 33) % limit as u64) as i64
+            };
+            let mut robots = Vec::new();
+            let mut used_positions = HashSet::new();
+            for row in 0..20 {
+                for col in (20 - row)..(20 + row + 1) {
+                    used_positions.insert((30 + col, 40 + row));
+                }
+            }
+            while used_positions.len() < 600 {
+                used_positions.insert((random(width), random(height)));
+            }
+            for (x, y) in used_positions {
+                let velocity = (random(width * 2) - width, random(height * 2) - height);
+                robots.push(format!(
+                    "p={},{} v={},{}",
+                    (x - velocity.0 * tree_second).rem_euclid(width),
+                    (y - velocity.1 * tree_second).rem_euclid(height),
+                    velocity.0,
+                    velocity.1
+                ));
+            }
+            robots.join("\n")
+        };
+        let (width, height) = (101, 103);
+        let tree_input = make_tree_input(width, height, 1234);
+        assert_eq!(day14_part2(&tree_input, width, height), 1234);
+        assert_eq!(
+            day14_find_tree(&tree_input, width, height, Day14TreeHeuristic::NoOverlap),
+            Some(1234)
+        );
+        assert!(day14_render(&tree_input, width, height, 1234)
+            .lines()
+            .any(|line| line.contains(&"1".repeat(39))));
+        // the width and height don't have to be coprime
+        assert_eq!(day14_part2(&make_tree_input(80, 60, 200), 80, 60), 200);
+        assert!(day14_part2(test_input, 10, 10) < 10);
+    }
+
+    #[test]
+    fn day15() {
+        let test_input = "########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+        let test_input_2 = "##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+        let test_input_3 = "#######
+#...#.#
+#.....#
+#..OO@#
+#..O..#
+#.....#
+#######
+
+<vv<<^^<<^^";
+        assert_eq!(day15_part1(test_input), 2028);
+        assert_eq!(day15_part1(test_input_2), 10092);
+        assert_eq!(day15_part2(test_input_2), 9021);
+
+        let maps = day15_maps_after_each_move(test_input_3, true);
+        assert_eq!(maps.len(), 12);
+        assert_eq!(
+            maps[0],
+            "##############
+##......##..##
+##..........##
+##....[][]@.##
+##....[]....##
+##..........##
+##############"
+        );
+        assert_eq!(
+            maps[11],
+            "##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############"
+        );
+    }
+
+    #[test]
+    fn day16() {
+        let test_input = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+        let test_input_2 = "#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################";
+        assert_eq!(day16_part1(test_input), 7036);
+        assert_eq!(day16_part1(test_input_2), 11048);
+        assert_eq!(day16_part2(test_input), 45);
+        assert_eq!(day16_part2(test_input_2), 64);
+
+        // a line of numbers, where each step can add 1 for 1 or 2 for 1
+        let paths = shortest_paths([0], |&n: &u32| {
+            if n < 4 {
+                vec![(n + 1, 1), (n + 2, 1)]
+            } else {
+                vec![]
+            }
+        });
+        assert_eq!(paths.costs[&4], 2);
+        assert_eq!(paths.costs[&5], 3);
+        assert_eq!(paths.states_on_paths_to(&[4]), HashSet::from([0, 2, 4]));
+        assert_eq!(
+            paths.states_on_paths_to(&[5]),
+            HashSet::from([0, 1, 2, 3, 5])
+        );
+    }
+
+    #[test]
+    fn day17() {
+        let test_input = "Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
+        let test_input_2 = "Register A: 2024
+Register B: 0
+Register C: 0
+
+Program: 0,3,5,4,3,0";
+        assert_eq!(day17_part1(test_input), "4,6,3,5,6,3,5,2,1,0");
+        assert_eq!(day17_part2(test_input_2), 117440);
+
+        let run = |registers, program: &[u8]| {
+            let mut computer = Day17Computer {
+                registers,
+                program: program.to_vec(),
+            };
+            let output = computer.run().unwrap();
+            (computer.registers, output)
+        };
+        assert_eq!(run([0, 0, 9], &[2, 6]).0[1], 1);
+        assert_eq!(run([10, 0, 0], &[5, 0, 5, 1, 5, 4]).1, vec![0, 1, 2]);
+        assert_eq!(
+            run([2024, 0, 0], &[0, 1, 5, 4, 3, 0]),
+            ([0, 0, 0], vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0])
+        );
+        assert_eq!(run([0, 29, 0], &[1, 7]).0[1], 26);
+        assert_eq!(run([0, 2024, 43690], &[4, 0]).0[1], 44354);
+
+        assert_eq!(
+            Day17Computer::new(test_input_2).disassemble(),
+            "0: adv 3
+2: out A
+4: jnz 0"
+        );
+        let mut computer = Day17Computer::new(test_input_2);
+        computer.registers[0] = 117440;
+        assert_eq!(computer.run().unwrap(), computer.program);
+
+        let mut stuck = Day17Computer {
+            registers: [1, 0, 0],
+            program: vec![3, 0],
+        };
+        assert_eq!(
+            stuck.run_with_max_steps(1000),
+            Err("program didn't halt within 1000 instructions".to_string())
+        );
+        // loops forever without output for any A but 0
+        assert_eq!(
+            Day17Computer::new("Register A: 0\n\nProgram: 3,0").find_quine_register_a(),
+            None
+        );
+    }
+
+    #[test]
+    fn day18() {
+        let test_input = "5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0";
+        assert_eq!(day18_part1(test_input, 7, 12), 22);
+        assert_eq!(day18_part2(test_input, 7), "6,1");
+
+        let bytes = day18_parse(test_input);
+        assert_eq!(day18_first_blocking_byte(&bytes, 7), Some(20));
+        assert_eq!(day18_steps_to_exit(&bytes[..20], 7), Some(24));
+        assert_eq!(day18_steps_to_exit(&bytes[..21], 7), None);
+        assert_eq!(day18_first_blocking_byte(&bytes[..20], 7), None);
+        // a byte landing on the exit blocks it straight away
+        assert_eq!(day18_first_blocking_byte(&[(1, 1), (2, 2)], 3), Some(1));
+        // and so does one landing on the start
+        assert_eq!(day18_steps_to_exit(&[(0, 0)], 3), None);
+        assert_eq!(day18_first_blocking_byte(&[(0, 0)], 3), Some(0));
+
+        // numbers reachable by doubling or adding 3, from either 1 or 2
+        let steps = fewest_steps(
+            [1, 2],
+            |&n: &u32| {
+                if n < 20 {
+                    vec![n * 2, n + 3]
+                } else {
+                    vec![]
+                }
+            },
+        );
+        assert_eq!(steps[&2], 0);
+        assert_eq!(steps[&4], 1);
+        assert_eq!(steps[&10], 2);
+        assert_eq!(steps[&20], 3);
+        assert!(!steps.contains_key(&3));
+    }
+
+    #[test]
+    fn day19() {
+        let test_input = "r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb";
+        assert_eq!(day19_part1(test_input), 6);
+        assert_eq!(day19_part2(test_input), 16);
+
+        let (towels, _) = day19_parse(test_input);
+        assert_eq!(day19_count_arrangements(&towels, "gbbr"), Some(4));
+        assert_eq!(day19_count_arrangements(&towels, "ubwu"), Some(0));
+        assert_eq!(day19_count_arrangements(&towels, ""), Some(1));
+        // runs of "r"s made of 1s and 2s follow the Fibonacci numbers
+        assert_eq!(
+            day19_count_arrangements(&["r", "rr"], &"r".repeat(64)),
+            Some(17167680177565)
+        );
+        assert_eq!(day19_count_arrangements(&["r"], &"r".repeat(1000)), Some(1));
+        assert_eq!(
+            day19_count_arrangements(&["r", "rr", "rrr"], &"r".repeat(200)),
+            None
+        );
+    }
+
+    #[test]
+    fn day20() {
+        let test_input = "###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+        assert_eq!(
+            day20_savings_histogram(test_input, 2, 1),
+            BTreeMap::from([
+                (2, 14),
+                (4, 14),
+                (6, 2),
+                (8, 4),
+                (10, 2),
+                (12, 3),
+                (20, 1),
+                (36, 1),
+                (38, 1),
+                (40, 1),
+                (64, 1)
+            ])
+        );
+        assert_eq!(
+            day20_savings_histogram(test_input, 20, 50),
+            BTreeMap::from([
+                (50, 32),
+                (52, 31),
+                (54, 29),
+                (56, 39),
+                (58, 25),
+                (60, 23),
+                (62, 20),
+                (64, 19),
+                (66, 12),
+                (68, 14),
+                (70, 12),
+                (72, 22),
+                (74, 4),
+                (76, 3)
+            ])
+        );
+        assert_eq!(day20_part1(test_input, 20), 5);
+        assert_eq!(day20_part2(test_input, 50), 285);
+
+        // cutting from the end into the dead end looks like it saves time if only the distances
+        // from the start are used
+        let dead_end = "#######
+#S....#
+#.###.#
+#E#...#
+#######";
+        assert!(day20_savings_histogram(dead_end, 2, 1).is_empty());
+        let hairpin = "#######
+#S....#
+#####.#
+#E....#
+#######";
+        assert_eq!(
+            day20_savings_histogram(hairpin, 2, 1),
+            BTreeMap::from([(2, 1), (4, 1), (6, 1), (8, 1)])
+        );
+    }
+
+    #[test]
+    fn day21() {
+        let test_input = "029A
+980A
+179A
+456A
+379A";
+        assert_eq!(day21_part1(test_input), 126384);
+        assert_eq!(day21_part2(test_input), 154115708116294);
+
+        // Presses the buttons through each layer of keypads, returning what gets typed on the
+        // numeric keypad.
+        fn type_code(presses: &str, num_of_directional_robots: usize) -> String {
+            let mut keys = presses.to_string();
+            for layer in 0..=num_of_directional_robots {
+                let keypad = if layer == num_of_directional_robots {
+                    &DAY21_NUMERIC_KEYPAD[..]
+                } else {
+                    &DAY21_DIRECTIONAL_KEYPAD[..]
+                };
+                let (mut x, mut y) = keypad
+                    .iter()
+                    .enumerate()
+                    .find_map(|(y, row)| row.find('A').map(|x| (x, y)))
+                    .unwrap();
+                let mut typed = String::new();
+                for key in keys.chars() {
+                    match key {
+                        '<' => x -= 1,
+                        '>' => x += 1,
+                        '^' => y -= 1,
+                        'v' => y += 1,
+                        _ => typed.push(keypad[y].as_bytes()[x] as char),
+                    }
+                    assert_ne!(keypad[y].as_bytes()[x], b' ', "robot arm over the gap");
+                }
+                keys = typed;
+            }
+            keys
+        }
+        for (code, length) in [
+            ("029A", 68),
+            ("980A", 60),
+            ("179A", 68),
+            ("456A", 64),
+            ("379A", 64),
+        ] {
+            let sequence = day21_shortest_sequence(code, 2);
+            assert_eq!(sequence.len(), length);
+            assert_eq!(type_code(&sequence, 2), code);
+        }
+        assert_eq!(day21_shortest_sequence("029A", 0).len(), 12);
+        assert_eq!(day21_shortest_sequence("029A", 1).len(), 28);
+        assert_eq!(type_code(&day21_shortest_sequence("029A", 4), 4), "029A");
+        assert_eq!(
+            KeypadSolver::new().fewest_presses("029A", 4),
+            day21_shortest_sequence("029A", 4).len() as u64
+        );
     }
 }